@@ -34,7 +34,7 @@ pub async fn execute(
         println!(
             "{} Retrieved {} supported currency codes",
             "Info:".bold().blue(),
-            codes.supported_codes.len()
+            codes.len()
         );
     }
 
@@ -1,8 +1,9 @@
-use client::ExchangeRateClient;
+use client::{Decimal, ExchangeRateClient, RoundingPolicy};
 use colored::Colorize;
 
 use crate::error::CliError;
 use crate::formatters;
+use crate::locale::Locale;
 use crate::utils;
 
 /// Execute the convert command
@@ -13,23 +14,28 @@ use crate::utils;
 /// * `amount` - The amount to convert
 /// * `from_currency` - The source currency code
 /// * `to_currency` - The target currency code
+/// * `rounding` - The rounding policy for the converted amount, if any
 /// * `format` - The output format (text, json, csv)
+/// * `locale` - The locale for text output
 /// * `verbose` - Whether to enable verbose output
 ///
 /// # Returns
 ///
 /// * `Result<(), CliError>` - Ok if successful, Err otherwise
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     client: &ExchangeRateClient,
-    amount: f64,
+    amount: Decimal,
     from_currency: &str,
     to_currency: &str,
+    rounding: Option<RoundingPolicy>,
     format: Option<&str>,
+    locale: &Locale,
     verbose: bool,
 ) -> Result<(), CliError> {
     // Validate currency codes
-    utils::validate_currency_code(from_currency)?;
-    utils::validate_currency_code(to_currency)?;
+    let from_currency = utils::validate_currency_code(from_currency)?;
+    let to_currency = utils::validate_currency_code(to_currency)?;
 
     if verbose {
         println!("{} Validating currency codes", "Info:".bold().blue());
@@ -44,15 +50,16 @@ pub async fn execute(
         );
     }
 
-    let rates = client.get_latest_rates(from_currency).await?;
-    let rate = rates.get_rate(to_currency).unwrap_or(0.0);
+    let rates = client.get_latest_rates_decimal(from_currency).await?;
+    utils::warn_if_stale(rates.stale, &rates.time_last_update_utc);
+    let rate = rates.get_rate(to_currency).unwrap_or_default();
 
     if verbose {
         println!(
             "{} Found rate: 1 {} = {:.4} {}",
             "Info:".bold().blue(),
             from_currency,
-            rate,
+            rate.round_dp(4),
             to_currency
         );
     }
@@ -62,15 +69,30 @@ pub async fn execute(
         println!("{} Converting amount", "Info:".bold().blue());
     }
 
-    let converted_amount = client.convert(amount, from_currency, to_currency).await?;
+    let mut converted_amount = client
+        .convert_decimal(amount, from_currency, to_currency)
+        .await?;
+
+    if let Some(policy) = rounding {
+        converted_amount = policy.round_decimal(converted_amount, to_currency);
+
+        if verbose {
+            println!(
+                "{} Rounded to {} {}",
+                "Info:".bold().blue(),
+                converted_amount,
+                to_currency
+            );
+        }
+    }
 
     if verbose {
         println!(
             "{} Conversion result: {:.2} {} = {:.2} {}",
             "Info:".bold().blue(),
-            amount,
+            amount.round_dp(2),
             from_currency,
-            converted_amount,
+            converted_amount.round_dp(2),
             to_currency
         );
     }
@@ -82,11 +104,12 @@ pub async fn execute(
 
     let output = formatters::format_conversion(
         amount,
-        from_currency,
-        to_currency,
+        from_currency.as_str(),
+        to_currency.as_str(),
         converted_amount,
         rate,
         format,
+        locale,
     )?;
     println!("{}", output);
 
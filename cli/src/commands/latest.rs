@@ -3,6 +3,7 @@ use colored::Colorize;
 
 use crate::error::CliError;
 use crate::formatters;
+use crate::locale::Locale;
 use crate::utils;
 
 /// Execute the latest command
@@ -12,6 +13,7 @@ use crate::utils;
 /// * `client` - The Exchange Rate API client
 /// * `base_currency` - The base currency code
 /// * `format` - The output format (text, json, csv)
+/// * `locale` - The locale for text output
 /// * `verbose` - Whether to enable verbose output
 ///
 /// # Returns
@@ -21,10 +23,11 @@ pub async fn execute(
     client: &ExchangeRateClient,
     base_currency: &str,
     format: Option<&str>,
+    locale: &Locale,
     verbose: bool,
 ) -> Result<(), CliError> {
     // Validate currency code
-    utils::validate_currency_code(base_currency)?;
+    let base_currency = utils::validate_currency_code(base_currency)?;
 
     if verbose {
         println!(
@@ -44,6 +47,7 @@ pub async fn execute(
     }
 
     let rates = client.get_latest_rates(base_currency).await?;
+    utils::warn_if_stale(rates.stale, &rates.time_last_update_utc);
 
     if verbose {
         println!(
@@ -59,7 +63,7 @@ pub async fn execute(
         println!("{} Formatting output", "Info:".bold().blue());
     }
 
-    let output = formatters::format_latest_rates(&rates, format)?;
+    let output = formatters::format_latest_rates(&rates, format, locale)?;
     println!("{}", output);
 
     Ok(())
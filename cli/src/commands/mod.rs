@@ -2,3 +2,5 @@ pub mod codes;
 pub mod convert;
 pub mod latest;
 pub mod pair;
+pub mod portfolio;
+pub mod quota;
@@ -1,4 +1,6 @@
-use client::ExchangeRateClient;
+use client::{
+    CurrencyCode, ExchangeRateClient, ExchangeRateError, PairConversionResponse, TargetData,
+};
 use colored::Colorize;
 
 use crate::error::CliError;
@@ -12,6 +14,7 @@ use crate::utils;
 /// * `client` - The Exchange Rate API client
 /// * `from_currency` - The source currency code
 /// * `to_currency` - The target currency code
+/// * `details` - Whether to fetch target currency details from the enriched endpoint
 /// * `format` - The output format (text, json, csv)
 /// * `verbose` - Whether to enable verbose output
 ///
@@ -22,12 +25,13 @@ pub async fn execute(
     client: &ExchangeRateClient,
     from_currency: &str,
     to_currency: &str,
+    details: bool,
     format: Option<&str>,
     verbose: bool,
 ) -> Result<(), CliError> {
     // Validate currency codes
-    utils::validate_currency_code(from_currency)?;
-    utils::validate_currency_code(to_currency)?;
+    let from_currency = utils::validate_currency_code(from_currency)?;
+    let to_currency = utils::validate_currency_code(to_currency)?;
 
     if verbose {
         println!(
@@ -48,16 +52,69 @@ pub async fn execute(
         );
     }
 
-    let rate = client
-        .get_pair_conversion(from_currency, to_currency)
-        .await?;
+    // Only ask for target currency details when they're wanted, as on plans without
+    // enriched data (or open access and other providers) that costs a second
+    // request. Fall back to the plain pair endpoint there, or when offline without
+    // enriched data cached
+    if !details {
+        let pair = client
+            .get_pair_conversion(from_currency, to_currency)
+            .await?;
+        return print_pair(&pair, None, from_currency, to_currency, format, verbose);
+    }
+
+    let (pair, target_data) = match client.get_enriched_pair(from_currency, to_currency).await {
+        Ok(enriched) => {
+            let (pair, target_data) = enriched.into_parts();
+            (pair, Some(target_data))
+        }
+        Err(
+            ExchangeRateError::PlanUpgradeRequired
+            | ExchangeRateError::MissingApiKey
+            | ExchangeRateError::NotCached(_)
+            | ExchangeRateError::NotSupported,
+        ) => {
+            if verbose {
+                println!(
+                    "{} Enriched data is not available, using standard pair rate",
+                    "Info:".bold().blue()
+                );
+            }
+            let pair = client
+                .get_pair_conversion(from_currency, to_currency)
+                .await?;
+            (pair, None)
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    print_pair(
+        &pair,
+        target_data.as_ref(),
+        from_currency,
+        to_currency,
+        format,
+        verbose,
+    )
+}
+
+/// Print a pair rate, with target currency details when available
+fn print_pair(
+    pair: &PairConversionResponse,
+    target_data: Option<&TargetData>,
+    from_currency: CurrencyCode,
+    to_currency: CurrencyCode,
+    format: Option<&str>,
+    verbose: bool,
+) -> Result<(), CliError> {
+    utils::warn_if_stale(pair.stale, &pair.time_last_update_utc);
 
     if verbose {
         println!(
             "{} Found rate: 1 {} = {:.4} {}",
             "Info:".bold().blue(),
             from_currency,
-            rate,
+            pair.conversion_rate,
             to_currency
         );
     }
@@ -67,7 +124,7 @@ pub async fn execute(
         println!("{} Formatting output", "Info:".bold().blue());
     }
 
-    let output = formatters::format_pair_rate(from_currency, to_currency, rate, format)?;
+    let output = formatters::format_pair_rate(pair, target_data, format)?;
     println!("{}", output);
 
     Ok(())
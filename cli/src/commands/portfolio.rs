@@ -0,0 +1,201 @@
+use client::{Decimal, ExchangeRateClient, Portfolio};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+
+use crate::error::CliError;
+use crate::formatters;
+use crate::locale::Locale;
+use crate::utils;
+
+/// Execute the portfolio command
+///
+/// # Arguments
+///
+/// * `client` - The Exchange Rate API client
+/// * `file` - The file listing the positions
+/// * `target_currency` - The currency to value the portfolio in
+/// * `format` - The output format (text, json, csv)
+/// * `locale` - The locale for text output
+/// * `verbose` - Whether to enable verbose output
+///
+/// # Returns
+///
+/// * `Result<(), CliError>` - Ok if successful, Err otherwise
+pub async fn execute(
+    client: &ExchangeRateClient,
+    file: &Path,
+    target_currency: &str,
+    format: Option<&str>,
+    locale: &Locale,
+    verbose: bool,
+) -> Result<(), CliError> {
+    // Validate currency code
+    let target_currency = utils::validate_currency_code(target_currency)?;
+
+    // Read the positions
+    if verbose {
+        println!(
+            "{} Reading positions from {}",
+            "Info:".bold().blue(),
+            file.display()
+        );
+    }
+
+    let contents = fs::read_to_string(file).map_err(|e| {
+        CliError::InvalidPortfolio(format!("Failed to read {}: {}", file.display(), e))
+    })?;
+    let portfolio = parse_positions(&contents)?;
+
+    if verbose {
+        println!(
+            "{} Valuing {} positions in {}",
+            "Info:".bold().blue(),
+            portfolio.positions().len(),
+            target_currency
+        );
+    }
+
+    let valuation = portfolio.value_in(client, target_currency).await?;
+    if let Some(position) = valuation.positions.first() {
+        utils::warn_if_stale(
+            valuation.stale,
+            &formatters::format_unix_time(position.time_last_update_unix),
+        );
+    }
+
+    // Format and print the result
+    if verbose {
+        println!("{} Formatting output", "Info:".bold().blue());
+    }
+
+    let output = formatters::format_portfolio(&valuation, format, locale)?;
+    println!("{}", output);
+
+    Ok(())
+}
+
+/// Parse positions from a file with one `CODE,AMOUNT` (or `CODE AMOUNT`) per line
+///
+/// Blank lines, lines starting with `#` and a `currency,amount` header are skipped.
+fn parse_positions(contents: &str) -> Result<Portfolio<Decimal>, CliError> {
+    let mut portfolio = Portfolio::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid_line = || {
+            CliError::InvalidPortfolio(format!(
+                "Line {}: expected CURRENCY,AMOUNT but found '{}'",
+                index + 1,
+                line
+            ))
+        };
+
+        let mut fields = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty());
+        let (Some(currency), Some(amount), None) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid_line());
+        };
+
+        if currency.eq_ignore_ascii_case("currency") {
+            continue;
+        }
+
+        let currency = utils::validate_currency_code(currency)
+            .map_err(|e| CliError::InvalidPortfolio(format!("Line {}: {}", index + 1, e)))?;
+        let amount: Decimal = amount.parse().map_err(|_| invalid_line())?;
+
+        portfolio.add(currency, amount);
+    }
+
+    if portfolio.is_empty() {
+        return Err(CliError::InvalidPortfolio(
+            "The file contains no positions".to_string(),
+        ));
+    }
+
+    Ok(portfolio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(contents: &str) -> Vec<(String, String)> {
+        parse_positions(contents)
+            .unwrap()
+            .positions()
+            .iter()
+            .map(|position| (position.currency.to_string(), position.amount.to_string()))
+            .collect()
+    }
+
+    fn position(currency: &str, amount: &str) -> (String, String) {
+        (currency.to_string(), amount.to_string())
+    }
+
+    fn error(contents: &str) -> String {
+        match parse_positions(contents) {
+            Err(CliError::InvalidPortfolio(msg)) => msg,
+            other => panic!("expected InvalidPortfolio, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_parse_positions() {
+        // Commas and whitespace both separate fields
+        assert_eq!(
+            positions("EUR,250.00\nusd 1000\n  JPY\t-50000  \nGBP , 10.5\n"),
+            vec![
+                position("EUR", "250.00"),
+                position("USD", "1000"),
+                position("JPY", "-50000"),
+                position("GBP", "10.5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_positions_skips_header_comments_and_blank_lines() {
+        assert_eq!(
+            positions("Currency,Amount\n# Savings\n\nEUR,250\n   # Cash\nCHF,40\n"),
+            vec![position("EUR", "250"), position("CHF", "40")]
+        );
+    }
+
+    #[test]
+    fn test_parse_positions_reports_malformed_lines() {
+        assert_eq!(
+            error("EUR,250\nUSD\n"),
+            "Line 2: expected CURRENCY,AMOUNT but found 'USD'"
+        );
+        assert_eq!(
+            error("EUR,250,GBP\n"),
+            "Line 1: expected CURRENCY,AMOUNT but found 'EUR,250,GBP'"
+        );
+        assert_eq!(
+            error("# Savings\nEUR,lots\n"),
+            "Line 2: expected CURRENCY,AMOUNT but found 'EUR,lots'"
+        );
+        // Amounts must be finite decimals
+        assert!(error("EUR,inf").starts_with("Line 1:"));
+        assert!(error("EUR,NaN").starts_with("Line 1:"));
+        // Currency codes are validated
+        assert!(error("EUR,1\nEURO,2").starts_with("Line 2: Invalid currency code"));
+    }
+
+    #[test]
+    fn test_parse_positions_rejects_empty_files() {
+        assert_eq!(error(""), "The file contains no positions");
+        assert_eq!(
+            error("currency,amount\n# Nothing yet\n"),
+            "The file contains no positions"
+        );
+    }
+}
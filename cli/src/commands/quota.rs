@@ -0,0 +1,85 @@
+use client::ExchangeRateClient;
+use colored::Colorize;
+
+use crate::error::CliError;
+use crate::formatters;
+
+/// Execute the quota command
+///
+/// # Arguments
+///
+/// * `client` - The Exchange Rate API client
+/// * `format` - The output format (text, json, csv)
+/// * `warning_threshold` - Warn if fewer requests than this remain
+/// * `verbose` - Whether to enable verbose output
+///
+/// # Returns
+///
+/// * `Result<(), CliError>` - Ok if successful, Err otherwise
+pub async fn execute(
+    client: &ExchangeRateClient,
+    format: Option<&str>,
+    warning_threshold: Option<u64>,
+    verbose: bool,
+) -> Result<(), CliError> {
+    // Get the request quota
+    if verbose {
+        println!("{} Fetching request quota", "Info:".bold().blue());
+    }
+
+    let quota = client.get_quota().await?;
+
+    if verbose {
+        println!(
+            "{} Retrieved quota: {} of {} requests remaining",
+            "Info:".bold().blue(),
+            quota.requests_remaining,
+            quota.plan_quota
+        );
+    }
+
+    // Format and print the result
+    if verbose {
+        println!("{} Formatting output", "Info:".bold().blue());
+    }
+
+    let output = formatters::format_quota(&quota, format)?;
+    println!("{}", output);
+
+    if let Some(threshold) = warning_threshold {
+        warn_if_below_threshold(&quota, threshold);
+    }
+
+    Ok(())
+}
+
+/// Check the remaining quota after another command and warn if it is running low
+///
+/// Failures are only reported in verbose mode, as the check is advisory.
+pub async fn check_threshold(client: &ExchangeRateClient, threshold: u64, verbose: bool) {
+    match client.get_quota().await {
+        Ok(quota) => warn_if_below_threshold(&quota, threshold),
+        Err(err) => {
+            if verbose {
+                println!(
+                    "{} Could not check request quota: {}",
+                    "Info:".bold().blue(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+/// Print a warning if the remaining requests have fallen below the threshold
+fn warn_if_below_threshold(quota: &client::QuotaResponse, threshold: u64) {
+    if quota.is_below(threshold) {
+        eprintln!(
+            "{} Only {} of {} API requests remaining. The quota refreshes on day {} of the month.",
+            "Warning:".bold().yellow(),
+            quota.requests_remaining,
+            quota.plan_quota,
+            quota.refresh_day_of_month
+        );
+    }
+}
@@ -1,12 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use crate::error::CliError;
 
 /// Configuration for the CLI
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// API key for the Exchange Rate API
     pub api_key: Option<String>,
@@ -14,10 +13,14 @@ pub struct Config {
     pub auth_method: Option<String>,
     /// Default output format (text, json, csv)
     pub default_format: Option<String>,
+    /// Locale for formatting amounts (e.g. de-DE)
+    pub locale: Option<String>,
     /// Whether to use colored output
     pub use_color: Option<bool>,
     /// Whether to use caching
     pub use_cache: Option<bool>,
+    /// Warn when fewer API requests than this remain in the monthly quota
+    pub quota_warning_threshold: Option<u64>,
 }
 
 impl Default for Config {
@@ -26,8 +29,10 @@ impl Default for Config {
             api_key: None,
             auth_method: Some("bearer".to_string()),
             default_format: Some("text".to_string()),
+            locale: None,
             use_color: Some(true),
             use_cache: Some(true),
+            quota_warning_threshold: None,
         }
     }
 }
@@ -74,7 +79,7 @@ impl Config {
 }
 
 /// Get the path to the configuration file
-fn get_config_path() -> Result<PathBuf, CliError> {
+pub fn get_config_path() -> Result<PathBuf, CliError> {
     let home_dir = dirs::home_dir().ok_or_else(|| {
         CliError::UnexpectedError("Could not determine home directory".to_string())
     })?;
@@ -85,6 +90,15 @@ fn get_config_path() -> Result<PathBuf, CliError> {
     Ok(config_path)
 }
 
+/// Get the path to the response cache database
+pub fn get_cache_path() -> Result<PathBuf, CliError> {
+    let cache_dir = dirs::cache_dir().ok_or_else(|| {
+        CliError::UnexpectedError("Could not determine cache directory".to_string())
+    })?;
+
+    Ok(cache_dir.join("exchangerate").join("cache.db"))
+}
+
 /// Create a new configuration file with default values
 pub fn create_default_config() -> Result<(), CliError> {
     let config = Config::default();
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::locale::Locale;
+
 /// Errors that can occur when using the CLI
 #[derive(Debug)]
 pub enum CliError {
@@ -9,16 +11,26 @@ pub enum CliError {
     InvalidCurrency(String),
     /// Invalid output format specified
     InvalidFormat(String),
+    /// Invalid rounding mode specified
+    InvalidRounding(String),
+    /// Unsupported locale specified
+    InvalidLocale(String),
+    /// Unknown rate provider specified
+    InvalidProvider(String),
     /// API key not provided
     MissingApiKey,
     /// Network-related error
     NetworkError(String),
+    /// Data needed offline that isn't in the cache
+    NotCached(String),
     /// Unexpected error that doesn't fit other categories
     UnexpectedError(String),
     /// Invalid configuration key
     InvalidConfigKey(String),
     /// Invalid configuration value
     InvalidConfigValue(String),
+    /// Invalid portfolio positions file
+    InvalidPortfolio(String),
 }
 
 impl fmt::Display for CliError {
@@ -31,7 +43,7 @@ impl fmt::Display for CliError {
             ),
             CliError::InvalidCurrency(code) => write!(
                 f,
-                "Invalid currency code: '{}'. Currency codes must be 3 uppercase letters (e.g., USD, EUR, GBP).",
+                "Invalid currency code: '{}'. Currency codes must be 3-letter ISO 4217 codes (e.g., USD, EUR, GBP).",
                 code
             ),
             CliError::InvalidFormat(format) => write!(
@@ -39,15 +51,36 @@ impl fmt::Display for CliError {
                 "Invalid output format: '{}'. Supported formats are: text, json, csv.",
                 format
             ),
+            CliError::InvalidRounding(mode) => write!(
+                f,
+                "Invalid rounding mode: '{}'. Supported modes are: half-even, half-up, toward-zero, up.",
+                mode
+            ),
+            CliError::InvalidLocale(locale) => write!(
+                f,
+                "Unsupported locale: '{}'. Supported locales are: {}.",
+                locale,
+                Locale::supported().collect::<Vec<_>>().join(", ")
+            ),
+            CliError::InvalidProvider(provider) => write!(
+                f,
+                "Unknown rate provider: '{}'. Supported providers are: exchangerate-api, ecb.",
+                provider
+            ),
             CliError::MissingApiKey => write!(
                 f,
-                "API key not provided. Use --api-key option or set EXCHANGE_RATE_API_KEY environment variable.\nGet your API key at https://www.exchangerate-api.com/"
+                "This command requires an API key. Use --api-key option or set EXCHANGE_RATE_API_KEY environment variable.\nGet your API key at https://www.exchangerate-api.com/"
             ),
             CliError::NetworkError(msg) => write!(
                 f,
                 "Network Error: {}. Please check your internet connection and try again.",
                 msg
             ),
+            CliError::NotCached(key) => write!(
+                f,
+                "No cached data for '{}' is available offline. Run the command without --offline to fetch and cache it first.",
+                key
+            ),
             CliError::UnexpectedError(msg) => write!(
                 f,
                 "Unexpected Error: {}. Please report this issue if it persists.",
@@ -55,6 +88,7 @@ impl fmt::Display for CliError {
             ),
             CliError::InvalidConfigKey(key) => write!(f, "Invalid configuration key: {}", key),
             CliError::InvalidConfigValue(msg) => write!(f, "Invalid configuration value: {}", msg),
+            CliError::InvalidPortfolio(msg) => write!(f, "Invalid portfolio file: {}", msg),
         }
     }
 }
@@ -65,9 +99,13 @@ impl From<client::ExchangeRateError> for CliError {
     fn from(err: client::ExchangeRateError) -> Self {
         match err {
             client::ExchangeRateError::MissingApiKey => CliError::MissingApiKey,
-            client::ExchangeRateError::UnsupportedCode => {
-                CliError::InvalidCurrency("Unsupported currency code".to_string())
+            client::ExchangeRateError::InvalidCurrencyCode(e) => {
+                CliError::InvalidCurrency(e.to_string())
             }
+            client::ExchangeRateError::UnsupportedCode => CliError::ApiError(
+                "Unsupported currency code. Run 'exchangerate codes' to list supported currencies"
+                    .to_string(),
+            ),
             client::ExchangeRateError::InvalidKey => CliError::ApiError(
                 "Invalid API key. Please check your API key and try again".to_string(),
             ),
@@ -77,6 +115,23 @@ impl From<client::ExchangeRateError> for CliError {
             client::ExchangeRateError::QuotaReached => CliError::ApiError(
                 "API quota reached. Please upgrade your plan or try again later".to_string(),
             ),
+            client::ExchangeRateError::PlanUpgradeRequired => CliError::ApiError(
+                "This feature is not available on your plan. Please upgrade your plan to use it"
+                    .to_string(),
+            ),
+            client::ExchangeRateError::NoDataAvailable => CliError::ApiError(
+                "No data is available for the requested currency or date".to_string(),
+            ),
+            client::ExchangeRateError::BudgetExhausted => CliError::ApiError(
+                "The monthly request budget is spent. Try again when it refreshes".to_string(),
+            ),
+            client::ExchangeRateError::NotCached(key) => CliError::NotCached(key),
+            client::ExchangeRateError::NotSupported => CliError::ApiError(
+                "This request is not supported by the selected rate provider".to_string(),
+            ),
+            client::ExchangeRateError::UnknownApiError(error_type) => {
+                CliError::ApiError(format!("The API returned an error: {}", error_type))
+            }
             client::ExchangeRateError::MalformedRequest => {
                 CliError::ApiError("Malformed request. This is likely a bug in the CLI".to_string())
             }
@@ -94,6 +149,7 @@ impl From<client::ExchangeRateError> for CliError {
                     CliError::NetworkError(format!("HTTP client error: {}", e))
                 }
             }
+            client::ExchangeRateError::TransportError(msg) => CliError::NetworkError(msg),
             client::ExchangeRateError::HttpError(status) => match status.as_u16() {
                 429 => CliError::ApiError("Too many requests. Please try again later".to_string()),
                 403 => {
@@ -110,10 +166,16 @@ impl From<client::ExchangeRateError> for CliError {
             client::ExchangeRateError::JsonError(e) => {
                 CliError::UnexpectedError(format!("Failed to parse API response: {}", e))
             }
+            client::ExchangeRateError::InvalidResponse(msg) => CliError::ApiError(format!(
+                "The rate provider returned an invalid response: {}",
+                msg
+            )),
+            client::ExchangeRateError::Overflow => CliError::UnexpectedError(
+                "A calculation overflowed. The amounts are too large to convert".to_string(),
+            ),
             client::ExchangeRateError::CacheError(e) => {
                 CliError::UnexpectedError(format!("Cache error: {}. Try using --no-cache", e))
             }
-            _ => CliError::UnexpectedError(format!("Unexpected error: {}", err)),
         }
     }
 }
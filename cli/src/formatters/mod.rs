@@ -1,10 +1,15 @@
-use client::ExchangeRateResponse;
+use chrono::DateTime;
+use client::{
+    CurrencyCode, Decimal, ExchangeRateResponse, PairConversionResponse, QuotaResponse, TargetData,
+    Valuation,
+};
 use colored::Colorize;
 use prettytable::{Cell, Row, Table, format};
 use serde_json::json;
 use std::collections::HashMap;
 
 use crate::error::CliError;
+use crate::locale::Locale;
 use crate::utils;
 
 pub enum OutputFormat {
@@ -29,6 +34,7 @@ impl TryFrom<&str> for OutputFormat {
 pub fn format_latest_rates(
     response: &ExchangeRateResponse,
     format: Option<&str>,
+    locale: &Locale,
 ) -> Result<String, CliError> {
     let format = match format {
         Some(fmt) => OutputFormat::try_from(fmt)?,
@@ -36,13 +42,16 @@ pub fn format_latest_rates(
     };
 
     match format {
-        OutputFormat::Text => format_latest_rates_text(response),
+        OutputFormat::Text => format_latest_rates_text(response, locale),
         OutputFormat::Json => format_latest_rates_json(response),
         OutputFormat::Csv => format_latest_rates_csv(response),
     }
 }
 
-fn format_latest_rates_text(response: &ExchangeRateResponse) -> Result<String, CliError> {
+fn format_latest_rates_text(
+    response: &ExchangeRateResponse,
+    locale: &Locale,
+) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Header
@@ -74,14 +83,14 @@ fn format_latest_rates_text(response: &ExchangeRateResponse) -> Result<String, C
     ]));
 
     // Add data rows
-    let mut rates: Vec<(&String, &f64)> = response.conversion_rates.iter().collect();
+    let mut rates: Vec<(&CurrencyCode, &f64)> = response.conversion_rates.iter().collect();
     rates.sort_by(|a, b| a.0.cmp(b.0));
 
     for (code, rate) in rates {
         table.add_row(Row::new(vec![
             Cell::new(""),
-            Cell::new(code),
-            Cell::new(&format!("{:.4}", rate)),
+            Cell::new(code.as_str()),
+            Cell::new(&format_rate(*rate, locale)),
         ]));
     }
 
@@ -114,7 +123,7 @@ fn format_latest_rates_csv(response: &ExchangeRateResponse) -> Result<String, Cl
     output.push_str("Currency Code,Rate\n");
 
     // Data rows
-    let mut rates: Vec<(&String, &f64)> = response.conversion_rates.iter().collect();
+    let mut rates: Vec<(&CurrencyCode, &f64)> = response.conversion_rates.iter().collect();
     rates.sort_by(|a, b| a.0.cmp(b.0));
 
     for (code, rate) in rates {
@@ -125,12 +134,13 @@ fn format_latest_rates_csv(response: &ExchangeRateResponse) -> Result<String, Cl
 }
 
 pub fn format_conversion(
-    amount: f64,
+    amount: Decimal,
     from_currency: &str,
     to_currency: &str,
-    converted_amount: f64,
-    rate: f64,
+    converted_amount: Decimal,
+    rate: Decimal,
     format: Option<&str>,
+    locale: &Locale,
 ) -> Result<String, CliError> {
     let format = match format {
         Some(fmt) => OutputFormat::try_from(fmt)?,
@@ -138,9 +148,14 @@ pub fn format_conversion(
     };
 
     match format {
-        OutputFormat::Text => {
-            format_conversion_text(amount, from_currency, to_currency, converted_amount, rate)
-        }
+        OutputFormat::Text => format_conversion_text(
+            amount,
+            from_currency,
+            to_currency,
+            converted_amount,
+            rate,
+            locale,
+        ),
         OutputFormat::Json => {
             format_conversion_json(amount, from_currency, to_currency, converted_amount, rate)
         }
@@ -151,17 +166,18 @@ pub fn format_conversion(
 }
 
 fn format_conversion_text(
-    amount: f64,
+    amount: Decimal,
     from_currency: &str,
     to_currency: &str,
-    converted_amount: f64,
-    rate: f64,
+    converted_amount: Decimal,
+    rate: Decimal,
+    locale: &Locale,
 ) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Format amounts with appropriate currency symbols and decimal places
-    let formatted_from_amount = utils::format_currency_amount(amount, from_currency);
-    let formatted_to_amount = utils::format_currency_amount(converted_amount, to_currency);
+    let formatted_from_amount = utils::format_currency_amount(amount, from_currency, locale);
+    let formatted_to_amount = utils::format_currency_amount(converted_amount, to_currency, locale);
 
     output.push_str(&format!(
         "{} {} {} = {} {}\n",
@@ -172,9 +188,9 @@ fn format_conversion_text(
         to_currency
     ));
     output.push_str(&format!(
-        "{} {:.4} {} per {}\n",
+        "{} {} {} per {}\n",
         "Rate:".bold().green(),
-        rate,
+        locale.format_number(rate, 4),
         to_currency,
         from_currency
     ));
@@ -183,11 +199,11 @@ fn format_conversion_text(
 }
 
 fn format_conversion_json(
-    amount: f64,
+    amount: Decimal,
     from_currency: &str,
     to_currency: &str,
-    converted_amount: f64,
-    rate: f64,
+    converted_amount: Decimal,
+    rate: Decimal,
 ) -> Result<String, CliError> {
     let json = json!({
         "amount": amount,
@@ -201,30 +217,178 @@ fn format_conversion_json(
 }
 
 fn format_conversion_csv(
-    amount: f64,
+    amount: Decimal,
     from_currency: &str,
     to_currency: &str,
-    converted_amount: f64,
-    rate: f64,
+    converted_amount: Decimal,
+    rate: Decimal,
 ) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Header
     output.push_str("Amount,From Currency,To Currency,Converted Amount,Rate\n");
 
-    // Data row
+    // Data row, with amounts in each currency's minor units
+    let from_places = utils::minor_units(from_currency);
+    let to_places = utils::minor_units(to_currency);
     output.push_str(&format!(
-        "{:.2},{},{},{:.2},{:.4}\n",
-        amount, from_currency, to_currency, converted_amount, rate
+        "{:.*},{},{},{:.*},{:.4}\n",
+        from_places as usize,
+        amount.round_dp(from_places),
+        from_currency,
+        to_currency,
+        to_places as usize,
+        converted_amount.round_dp(to_places),
+        rate.round_dp(4)
     ));
 
     Ok(output)
 }
 
+pub fn format_portfolio(
+    valuation: &Valuation<Decimal>,
+    format: Option<&str>,
+    locale: &Locale,
+) -> Result<String, CliError> {
+    let format = match format {
+        Some(fmt) => OutputFormat::try_from(fmt)?,
+        None => OutputFormat::Text,
+    };
+
+    match format {
+        OutputFormat::Text => format_portfolio_text(valuation, locale),
+        OutputFormat::Json => format_portfolio_json(valuation),
+        OutputFormat::Csv => format_portfolio_csv(valuation),
+    }
+}
+
+fn format_portfolio_text(
+    valuation: &Valuation<Decimal>,
+    locale: &Locale,
+) -> Result<String, CliError> {
+    let mut output = String::new();
+    let target = valuation.target.as_str();
+
+    // Create table
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+
+    // Add header row
+    table.set_titles(Row::new(vec![
+        Cell::new("Code").style_spec("Fb"),
+        Cell::new("Amount").style_spec("Fb"),
+        Cell::new("Rate").style_spec("Fb"),
+        Cell::new(&format!("Value ({})", target)).style_spec("Fb"),
+        Cell::new("Rate Updated").style_spec("Fb"),
+    ]));
+
+    // Add data rows, in the order of the file
+    for position in &valuation.positions {
+        let currency = position.currency.as_str();
+
+        table.add_row(Row::new(vec![
+            Cell::new(currency),
+            Cell::new(&utils::format_currency_amount(
+                position.amount,
+                currency,
+                locale,
+            ))
+            .style_spec("r"),
+            Cell::new(&locale.format_number(position.rate, 4)).style_spec("r"),
+            Cell::new(&utils::format_currency_amount(
+                position.value,
+                target,
+                locale,
+            ))
+            .style_spec("r"),
+            Cell::new(&format_unix_time(position.time_last_update_unix)),
+        ]));
+    }
+
+    // Convert table to string
+    output.push_str(&table.to_string());
+
+    output.push_str(&format!(
+        "\n{} {} {}\n",
+        "Total Value:".bold().green(),
+        utils::format_currency_amount(valuation.total, target, locale),
+        target
+    ));
+
+    Ok(output)
+}
+
+fn format_portfolio_json(valuation: &Valuation<Decimal>) -> Result<String, CliError> {
+    let positions: Vec<_> = valuation
+        .positions
+        .iter()
+        .map(|position| {
+            json!({
+                "currency": position.currency,
+                "amount": position.amount,
+                "rate": position.rate,
+                "value": position.value,
+                "last_updated": format_unix_time(position.time_last_update_unix)
+            })
+        })
+        .collect();
+
+    let json = json!({
+        "target_currency": valuation.target,
+        "total": valuation.total,
+        "positions": positions
+    });
+
+    Ok(serde_json::to_string_pretty(&json)?)
+}
+
+fn format_portfolio_csv(valuation: &Valuation<Decimal>) -> Result<String, CliError> {
+    let mut output = String::new();
+
+    // Header
+    output.push_str("Currency,Amount,Rate,Target Currency,Value,Last Updated Unix\n");
+
+    // Data rows, with amounts in each currency's minor units
+    let to_places = utils::minor_units(valuation.target.as_str()) as usize;
+    for position in &valuation.positions {
+        let from_places = utils::minor_units(position.currency.as_str()) as usize;
+        output.push_str(&format!(
+            "{},{:.*},{:.4},{},{:.*},{}\n",
+            position.currency,
+            from_places,
+            position.amount,
+            position.rate,
+            valuation.target,
+            to_places,
+            position.value,
+            position.time_last_update_unix
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Formats a rate for the locale, or as a plain number if it's outside the range of
+/// `Decimal` (e.g. infinite)
+fn format_rate(rate: f64, locale: &Locale) -> String {
+    match Decimal::try_from(rate) {
+        Ok(rate) => locale.format_number(rate, 4),
+        Err(_) => format!("{:.4}", rate),
+    }
+}
+
+/// Formats a Unix timestamp like the API's `time_last_update_utc` fields
+pub fn format_unix_time(timestamp: u64) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|time| time.to_rfc2822())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
 pub fn format_pair_rate(
-    from_currency: &str,
-    to_currency: &str,
-    rate: f64,
+    pair: &PairConversionResponse,
+    target_data: Option<&TargetData>,
     format: Option<&str>,
 ) -> Result<String, CliError> {
     let format = match format {
@@ -233,62 +397,188 @@ pub fn format_pair_rate(
     };
 
     match format {
-        OutputFormat::Text => format_pair_rate_text(from_currency, to_currency, rate),
-        OutputFormat::Json => format_pair_rate_json(from_currency, to_currency, rate),
-        OutputFormat::Csv => format_pair_rate_csv(from_currency, to_currency, rate),
+        OutputFormat::Text => format_pair_rate_text(pair, target_data),
+        OutputFormat::Json => format_pair_rate_json(pair, target_data),
+        OutputFormat::Csv => format_pair_rate_csv(pair, target_data),
     }
 }
 
 fn format_pair_rate_text(
-    from_currency: &str,
-    to_currency: &str,
-    rate: f64,
+    pair: &PairConversionResponse,
+    target_data: Option<&TargetData>,
 ) -> Result<String, CliError> {
     let mut output = String::new();
 
+    // Use the symbol provided by the API when available
+    let symbol = target_data
+        .and_then(TargetData::symbol)
+        .unwrap_or_else(|| utils::get_currency_symbol(pair.target_code.as_str()).to_string());
+
     output.push_str(&format!(
-        "{} 1 {} = {:.4} {}\n",
+        "{} 1 {} = {}{:.4} {}\n",
         "Conversion Rate:".bold().green(),
-        from_currency,
-        rate,
-        to_currency
+        pair.base_code,
+        symbol,
+        pair.conversion_rate,
+        pair.target_code
+    ));
+
+    if let Some(target_data) = target_data {
+        output.push_str(&format!(
+            "{} {} ({})\n",
+            "Currency:".bold().green(),
+            target_data.currency_name,
+            target_data.locale
+        ));
+        output.push_str(&format!(
+            "{} {}\n",
+            "Flag:".bold().green(),
+            target_data.flag_url
+        ));
+    } else if let Some(currency) = pair.target_code.currency() {
+        output.push_str(&format!(
+            "{} {}\n",
+            "Currency:".bold().green(),
+            currency.name()
+        ));
+    }
+
+    output.push_str(&format!(
+        "{} {}\n",
+        "Last Updated:".bold().green(),
+        pair.time_last_update_utc
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Next Update:".bold().green(),
+        pair.time_next_update_utc
     ));
 
     Ok(output)
 }
 
 fn format_pair_rate_json(
-    from_currency: &str,
-    to_currency: &str,
-    rate: f64,
+    pair: &PairConversionResponse,
+    target_data: Option<&TargetData>,
 ) -> Result<String, CliError> {
-    let json = json!({
-        "from_currency": from_currency,
-        "to_currency": to_currency,
-        "rate": rate
+    let mut json = json!({
+        "from_currency": pair.base_code,
+        "to_currency": pair.target_code,
+        "rate": pair.conversion_rate,
+        "last_updated": pair.time_last_update_utc,
+        "next_update": pair.time_next_update_utc
     });
 
+    if let Some(target_data) = target_data {
+        json["target"] = json!({
+            "locale": target_data.locale,
+            "two_letter_code": target_data.two_letter_code,
+            "currency_name": target_data.currency_name,
+            "currency_name_short": target_data.currency_name_short,
+            "symbol": target_data.symbol(),
+            "flag_url": target_data.flag_url
+        });
+    }
+
     Ok(serde_json::to_string_pretty(&json)?)
 }
 
 fn format_pair_rate_csv(
-    from_currency: &str,
-    to_currency: &str,
-    rate: f64,
+    pair: &PairConversionResponse,
+    target_data: Option<&TargetData>,
 ) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Header
-    output.push_str("From Currency,To Currency,Rate\n");
+    output.push_str("From Currency,To Currency,Rate,Currency Name,Symbol\n");
+
+    // Data row (metadata columns are empty when enriched data is unavailable)
+    let (name, symbol) = match target_data {
+        Some(target_data) => (
+            target_data.currency_name.clone(),
+            target_data.symbol().unwrap_or_default(),
+        ),
+        None => (String::new(), String::new()),
+    };
+    output.push_str(&format!(
+        "{},{},{:.4},{},{}\n",
+        pair.base_code, pair.target_code, pair.conversion_rate, name, symbol
+    ));
+
+    Ok(output)
+}
+
+pub fn format_quota(quota: &QuotaResponse, format: Option<&str>) -> Result<String, CliError> {
+    let format = match format {
+        Some(fmt) => OutputFormat::try_from(fmt)?,
+        None => OutputFormat::Text,
+    };
+
+    match format {
+        OutputFormat::Text => format_quota_text(quota),
+        OutputFormat::Json => format_quota_json(quota),
+        OutputFormat::Csv => format_quota_csv(quota),
+    }
+}
+
+fn format_quota_text(quota: &QuotaResponse) -> Result<String, CliError> {
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "{} {}\n",
+        "Plan Quota:".bold().green(),
+        quota.plan_quota
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Requests Used:".bold().green(),
+        quota.requests_used()
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Requests Remaining:".bold().green(),
+        quota.requests_remaining
+    ));
+    output.push_str(&format!(
+        "{} Day {} of each month\n",
+        "Quota Refreshes:".bold().green(),
+        quota.refresh_day_of_month
+    ));
+
+    Ok(output)
+}
+
+fn format_quota_json(quota: &QuotaResponse) -> Result<String, CliError> {
+    let json = json!({
+        "plan_quota": quota.plan_quota,
+        "requests_used": quota.requests_used(),
+        "requests_remaining": quota.requests_remaining,
+        "refresh_day_of_month": quota.refresh_day_of_month
+    });
+
+    Ok(serde_json::to_string_pretty(&json)?)
+}
+
+fn format_quota_csv(quota: &QuotaResponse) -> Result<String, CliError> {
+    let mut output = String::new();
+
+    // Header
+    output.push_str("Plan Quota,Requests Used,Requests Remaining,Refresh Day Of Month\n");
 
     // Data row
-    output.push_str(&format!("{},{},{:.4}\n", from_currency, to_currency, rate));
+    output.push_str(&format!(
+        "{},{},{},{}\n",
+        quota.plan_quota,
+        quota.requests_used(),
+        quota.requests_remaining,
+        quota.refresh_day_of_month
+    ));
 
     Ok(output)
 }
 
 pub fn format_currency_codes(
-    codes: &[(String, String)],
+    codes: &[(CurrencyCode, String)],
     format: Option<&str>,
 ) -> Result<String, CliError> {
     let format = match format {
@@ -303,7 +593,7 @@ pub fn format_currency_codes(
     }
 }
 
-fn format_currency_codes_text(codes: &[(String, String)]) -> Result<String, CliError> {
+fn format_currency_codes_text(codes: &[(CurrencyCode, String)]) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Header
@@ -324,7 +614,7 @@ fn format_currency_codes_text(codes: &[(String, String)]) -> Result<String, CliE
 
     // Add data rows
     for (code, name) in codes {
-        table.add_row(Row::new(vec![Cell::new(code), Cell::new(name)]));
+        table.add_row(Row::new(vec![Cell::new(code.as_str()), Cell::new(name)]));
     }
 
     // Convert table to string
@@ -338,7 +628,7 @@ fn format_currency_codes_text(codes: &[(String, String)]) -> Result<String, CliE
     Ok(output)
 }
 
-fn format_currency_codes_json(codes: &[(String, String)]) -> Result<String, CliError> {
+fn format_currency_codes_json(codes: &[(CurrencyCode, String)]) -> Result<String, CliError> {
     let mut map = HashMap::new();
     for (code, name) in codes {
         map.insert(code, name);
@@ -352,7 +642,7 @@ fn format_currency_codes_json(codes: &[(String, String)]) -> Result<String, CliE
     Ok(serde_json::to_string_pretty(&json)?)
 }
 
-fn format_currency_codes_csv(codes: &[(String, String)]) -> Result<String, CliError> {
+fn format_currency_codes_csv(codes: &[(CurrencyCode, String)]) -> Result<String, CliError> {
     let mut output = String::new();
 
     // Header
@@ -365,3 +655,48 @@ fn format_currency_codes_csv(codes: &[(String, String)]) -> Result<String, CliEr
 
     Ok(output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rate() {
+        let de_de = Locale::parse("de-DE").unwrap();
+        assert_eq!(format_rate(1234.56789, &de_de), "1.234,5679");
+        // Rates outside the range of Decimal aren't shown as zero
+        assert_eq!(format_rate(f64::INFINITY, &de_de), "inf");
+        assert_eq!(format_rate(1e30, &de_de), format!("{:.4}", 1e30));
+    }
+
+    #[test]
+    fn test_format_portfolio_keeps_decimal_amounts() {
+        use client::PositionValuation;
+        use std::str::FromStr;
+
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        let valuation = Valuation {
+            target: "GBP".parse().unwrap(),
+            total: dec("12345678901234567890.12"),
+            positions: vec![PositionValuation {
+                currency: "USD".parse().unwrap(),
+                amount: dec("24691357802469135780.24"),
+                rate: dec("0.5"),
+                value: dec("12345678901234567890.12"),
+                time_last_update_unix: 1747180802,
+            }],
+            stale: false,
+        };
+
+        // Amounts beyond f64 precision are printed exactly
+        let csv = format_portfolio(&valuation, Some("csv"), &Locale::default()).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("USD,24691357802469135780.24,0.5000,GBP,12345678901234567890.12,1747180802")
+        );
+
+        colored::control::set_override(false);
+        let text = format_portfolio(&valuation, None, &Locale::default()).unwrap();
+        assert!(text.contains("Total Value: £12,345,678,901,234,567,890.12 GBP"));
+    }
+}
@@ -0,0 +1,288 @@
+use client::Decimal;
+use std::env;
+
+/// Number and currency formatting conventions for a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// BCP 47 language tag, e.g. `de-DE`
+    tag: &'static str,
+    /// Separator between the integer and fractional parts
+    decimal_separator: &'static str,
+    /// Separator between digit groups
+    group_separator: &'static str,
+    /// Whether digits are grouped in twos after the first thousand (e.g. 12,34,567)
+    indian_grouping: bool,
+    /// Whether the currency symbol follows the amount
+    symbol_after: bool,
+    /// Whether a space separates the currency symbol and the amount
+    symbol_space: bool,
+}
+
+/// Non-breaking space, so amounts aren't split across lines
+const NBSP: &str = "\u{a0}";
+
+/// Narrow non-breaking space, used for grouping in French
+const NNBSP: &str = "\u{202f}";
+
+/// Supported locales, with the default first
+///
+/// When only the language of a requested locale matches, the first entry for
+/// that language is used.
+const LOCALES: &[Locale] = &[
+    locale("en-US", ".", ",", false, false, false),
+    locale("en-GB", ".", ",", false, false, false),
+    locale("en-IN", ".", ",", true, false, false),
+    locale("de-DE", ",", ".", false, true, true),
+    locale("de-CH", ".", "’", false, false, true),
+    locale("fr-FR", ",", NNBSP, false, true, true),
+    locale("es-ES", ",", ".", false, true, true),
+    locale("it-IT", ",", ".", false, true, true),
+    locale("nl-NL", ",", ".", false, false, true),
+    locale("pt-BR", ",", ".", false, false, true),
+    locale("pt-PT", ",", NBSP, false, true, true),
+    locale("da-DK", ",", ".", false, true, true),
+    locale("sv-SE", ",", NBSP, false, true, true),
+    locale("pl-PL", ",", NBSP, false, true, true),
+    locale("ru-RU", ",", NBSP, false, true, true),
+    locale("ja-JP", ".", ",", false, false, false),
+    locale("zh-CN", ".", ",", false, false, false),
+    locale("ko-KR", ".", ",", false, false, false),
+];
+
+const fn locale(
+    tag: &'static str,
+    decimal_separator: &'static str,
+    group_separator: &'static str,
+    indian_grouping: bool,
+    symbol_after: bool,
+    symbol_space: bool,
+) -> Locale {
+    Locale {
+        tag,
+        decimal_separator,
+        group_separator,
+        indian_grouping,
+        symbol_after,
+        symbol_space,
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        LOCALES[0]
+    }
+}
+
+impl Locale {
+    /// Parses a locale such as `de-DE`, `de_DE.UTF-8` or `de`
+    ///
+    /// The POSIX `C` locale maps to the default (`en-US`). Returns `None` if the
+    /// language isn't supported.
+    pub fn parse(value: &str) -> Option<Self> {
+        // Drop any encoding or modifier, e.g. "de_DE.UTF-8@euro"
+        let name = value.split(['.', '@']).next().unwrap_or_default();
+        let name = name.trim().replace('_', "-");
+
+        if name.eq_ignore_ascii_case("C") || name.eq_ignore_ascii_case("POSIX") {
+            return Some(Self::default());
+        }
+
+        let language = name.split('-').next().unwrap_or_default();
+
+        LOCALES
+            .iter()
+            .find(|locale| locale.tag.eq_ignore_ascii_case(&name))
+            .or_else(|| {
+                LOCALES
+                    .iter()
+                    .find(|locale| locale.language().eq_ignore_ascii_case(language))
+            })
+            .copied()
+    }
+
+    /// Gets the locale from `LC_ALL`, `LC_MONETARY` or `LANG`, in POSIX precedence order
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MONETARY", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// The supported locale tags
+    pub fn supported() -> impl Iterator<Item = &'static str> {
+        LOCALES.iter().map(|locale| locale.tag)
+    }
+
+    /// The BCP 47 language tag, e.g. `de-DE`
+    pub fn tag(&self) -> &'static str {
+        self.tag
+    }
+
+    fn language(&self) -> &'static str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+
+    /// Formats a number with digit grouping and the locale's decimal separator
+    pub fn format_number(&self, value: Decimal, decimal_places: u32) -> String {
+        let formatted = format!(
+            "{:.*}",
+            decimal_places as usize,
+            value.round_dp(decimal_places).abs()
+        );
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut output = String::new();
+        if value.is_sign_negative() && !value.round_dp(decimal_places).is_zero() {
+            output.push('-');
+        }
+        output.push_str(&self.group_digits(integer));
+        if let Some(fraction) = fraction {
+            output.push_str(self.decimal_separator);
+            output.push_str(fraction);
+        }
+
+        output
+    }
+
+    /// Formats a currency amount, placing the symbol as the locale expects
+    ///
+    /// An empty symbol formats the number alone.
+    pub fn format_amount(&self, value: Decimal, decimal_places: u32, symbol: &str) -> String {
+        if symbol.is_empty() {
+            return self.format_number(value, decimal_places);
+        }
+
+        // The sign goes before a leading symbol, e.g. -$1.00 rather than $-1.00
+        let number = self.format_number(value.abs(), decimal_places);
+        let sign = if value.is_sign_negative() && !value.round_dp(decimal_places).is_zero() {
+            "-"
+        } else {
+            ""
+        };
+
+        let space = if self.symbol_space { NBSP } else { "" };
+        if self.symbol_after {
+            format!("{}{}{}{}", sign, number, space, symbol)
+        } else {
+            format!("{}{}{}{}", sign, symbol, space, number)
+        }
+    }
+
+    /// Inserts group separators into a string of ASCII digits
+    fn group_digits(&self, digits: &str) -> String {
+        let len = digits.len();
+        let mut output = String::with_capacity(len + len / 2);
+
+        for (index, digit) in digits.chars().enumerate() {
+            let remaining = len - index;
+            let is_group_start = if self.indian_grouping && remaining > 3 {
+                // Groups of two above the last three digits
+                (remaining - 3).is_multiple_of(2)
+            } else {
+                remaining.is_multiple_of(3)
+            };
+
+            if index > 0 && is_group_start {
+                output.push_str(self.group_separator);
+            }
+            output.push(digit);
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn locale(tag: &str) -> Locale {
+        Locale::parse(tag).unwrap()
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(
+            locale("en-US").format_number(dec("1234567.891"), 2),
+            "1,234,567.89"
+        );
+        assert_eq!(
+            locale("de-DE").format_number(dec("1234567.891"), 2),
+            "1.234.567,89"
+        );
+        assert_eq!(
+            locale("fr-FR").format_number(dec("1234567.891"), 2),
+            "1\u{202f}234\u{202f}567,89"
+        );
+        assert_eq!(locale("en-US").format_number(dec("999"), 0), "999");
+        assert_eq!(locale("en-US").format_number(dec("-1000"), 0), "-1,000");
+    }
+
+    #[test]
+    fn test_indian_grouping() {
+        let en_in = locale("en-IN");
+        assert_eq!(en_in.format_number(dec("1234567.89"), 2), "12,34,567.89");
+        assert_eq!(en_in.format_number(dec("123456789"), 0), "12,34,56,789");
+        assert_eq!(en_in.format_number(dec("12345"), 0), "12,345");
+        assert_eq!(en_in.format_number(dec("999"), 0), "999");
+    }
+
+    #[test]
+    fn test_format_amount_places_sign_and_symbol() {
+        // The sign goes before a leading symbol
+        assert_eq!(
+            locale("en-US").format_amount(dec("-1234.5"), 2, "$"),
+            "-$1,234.50"
+        );
+        // And before the number when the symbol follows it
+        assert_eq!(
+            locale("de-DE").format_amount(dec("-1234.5"), 2, "€"),
+            "-1.234,50\u{a0}€"
+        );
+        assert_eq!(
+            locale("de-CH").format_amount(dec("1234.5"), 2, "CHF"),
+            "CHF\u{a0}1’234.50"
+        );
+        // An empty symbol formats the number alone
+        assert_eq!(locale("en-US").format_amount(dec("-1.5"), 2, ""), "-1.50");
+    }
+
+    #[test]
+    fn test_negative_zero_is_unsigned() {
+        let en_us = locale("en-US");
+        assert_eq!(en_us.format_number(dec("-0.001"), 2), "0.00");
+        assert_eq!(en_us.format_amount(dec("-0.001"), 2, "$"), "$0.00");
+        assert_eq!(
+            locale("de-DE").format_amount(dec("-0.001"), 2, "€"),
+            "0,00\u{a0}€"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Locale::parse("C"), Some(Locale::default()));
+        assert_eq!(Locale::parse("POSIX"), Some(Locale::default()));
+        assert_eq!(Locale::default().tag(), "en-US");
+
+        // POSIX names, with or without an encoding and modifier
+        assert_eq!(locale("de_DE.UTF-8").tag(), "de-DE");
+        assert_eq!(locale("de_DE.UTF-8@euro").tag(), "de-DE");
+        assert_eq!(locale("en_in").tag(), "en-IN");
+
+        // An unsupported region falls back to the first locale for the language
+        assert_eq!(locale("fr").tag(), "fr-FR");
+        assert_eq!(locale("fr-CA").tag(), "fr-FR");
+        assert_eq!(locale("pt_AO.UTF-8").tag(), "pt-BR");
+
+        assert_eq!(Locale::parse("xx-YY"), None);
+    }
+}
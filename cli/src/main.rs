@@ -1,17 +1,22 @@
 use clap::{Parser, Subcommand};
-use client::ExchangeRateClient;
+use client::{Decimal, EcbProvider, ExchangeRateClient, NetworkPolicy, SqliteCache};
 use colored::Colorize;
 use std::env;
+use std::fs;
+use std::path::PathBuf;
 use std::process;
+use std::sync::Arc;
 
 mod commands;
 mod config;
 mod error;
 mod formatters;
+mod locale;
 mod utils;
 
 use config::Config;
 use error::CliError;
+use locale::Locale;
 
 #[derive(Parser)]
 #[command(
@@ -19,7 +24,7 @@ use error::CliError;
     about = "Command line interface for the Exchange Rate API",
     version,
     author,
-    long_about = "A command line tool to interact with the Exchange Rate API (https://www.exchangerate-api.com/), providing currency conversion and exchange rate information. Requires an API key which can be obtained for free from the Exchange Rate API website."
+    long_about = "A command line tool to interact with the Exchange Rate API (https://www.exchangerate-api.com/), providing currency conversion and exchange rate information. An API key can be obtained for free from the Exchange Rate API website. Without one, the free open access endpoint is used, which only provides latest rates updated once a day."
 )]
 struct Cli {
     /// API key for the Exchange Rate API (can also be set via EXCHANGE_RATE_API_KEY env var)
@@ -38,6 +43,15 @@ struct Cli {
     )]
     auth_method: Option<String>,
 
+    /// Rate provider (exchangerate-api, ecb)
+    #[arg(
+        long,
+        value_name = "PROVIDER",
+        default_value = "exchangerate-api",
+        help = "Where to get rates from. 'exchangerate-api' uses the Exchange Rate API. 'ecb' uses the European Central Bank's euro reference rates for about 30 currencies, published on working days, and needs no API key."
+    )]
+    provider: String,
+
     /// Output format (text, json, csv)
     #[arg(
         long,
@@ -46,6 +60,14 @@ struct Cli {
     )]
     format: Option<String>,
 
+    /// Locale for formatting amounts (e.g. en-US, de-DE)
+    #[arg(
+        long,
+        value_name = "LOCALE",
+        help = "Locale for formatting amounts in text output, e.g. 'en-US' or 'de-DE'. Defaults to the 'locale' config key, then the LC_ALL, LC_MONETARY or LANG environment variables."
+    )]
+    locale: Option<String>,
+
     /// Disable colored output
     #[arg(
         long,
@@ -60,6 +82,22 @@ struct Cli {
     )]
     no_cache: bool,
 
+    /// Only use cached data
+    #[arg(
+        long,
+        conflicts_with = "no_cache",
+        help = "Never call the API, using only responses cached by earlier commands, even if they have expired. Fails if the data needed isn't cached."
+    )]
+    offline: bool,
+
+    /// Warn when the remaining API quota falls below this many requests
+    #[arg(
+        long,
+        value_name = "REQUESTS",
+        help = "Warn when fewer than this many API requests remain in your monthly quota. Checking the quota does not count against it."
+    )]
+    quota_warning: Option<u64>,
+
     /// Enable verbose output
     #[arg(
         short,
@@ -95,7 +133,7 @@ enum Commands {
     Convert {
         /// Amount to convert
         #[arg(help = "The amount to convert. Can be any positive number.")]
-        amount: f64,
+        amount: Decimal,
 
         /// Source currency code (e.g., USD)
         #[arg(
@@ -108,6 +146,21 @@ enum Commands {
             help = "The target currency code (e.g., USD, EUR, GBP). Must be a valid 3-letter currency code."
         )]
         to_currency: String,
+
+        /// Rounding mode (half-even, half-up, toward-zero, up)
+        #[arg(
+            long,
+            value_name = "MODE",
+            help = "Round the converted amount to the target currency's minor unit. 'half-even' (banker's rounding), 'half-up', 'toward-zero' or 'up' (away from zero)."
+        )]
+        rounding: Option<String>,
+
+        /// Round to the smallest cash denomination
+        #[arg(
+            long,
+            help = "Round to the smallest coin in circulation, e.g. 0.05 for CHF or whole units for SEK. Uses half-even unless --rounding is given."
+        )]
+        cash: bool,
     },
 
     /// Get direct conversion rate between two currencies
@@ -127,6 +180,33 @@ enum Commands {
             help = "The target currency code (e.g., USD, EUR, GBP). Must be a valid 3-letter currency code."
         )]
         to_currency: String,
+
+        /// Include target currency details from the enriched endpoint
+        #[arg(
+            long,
+            help = "Include the target currency's name, locale, symbol and flag from the enriched pair endpoint. Requires a Pro or Business plan; other plans fall back to the standard rate after a failed request."
+        )]
+        details: bool,
+    },
+
+    /// Value a portfolio of balances in several currencies
+    #[command(
+        about = "Value a portfolio of balances in several currencies",
+        long_about = "Reads positions from a file and values each of them, and the total, in a single currency. The file has one position per line as CURRENCY,AMOUNT (e.g. 'EUR,250.00'); blank lines and lines starting with '#' are ignored. All rates come from one request for the target currency."
+    )]
+    Portfolio {
+        /// File listing the positions
+        #[arg(
+            value_name = "FILE",
+            help = "Path to a file with one CURRENCY,AMOUNT position per line."
+        )]
+        file: PathBuf,
+
+        /// Target currency code (e.g., USD)
+        #[arg(
+            help = "The currency to value the portfolio in (e.g., USD, EUR, GBP). Must be a valid 3-letter currency code."
+        )]
+        target_currency: String,
     },
 
     /// List all supported currency codes
@@ -136,6 +216,13 @@ enum Commands {
     )]
     Codes,
 
+    /// Show the API request quota
+    #[command(
+        about = "Show the API request quota",
+        long_about = "Shows the monthly request quota for your API key, how many requests remain, and the day of the month on which the quota refreshes."
+    )]
+    Quota,
+
     /// Manage configuration
     #[command(
         about = "Manage configuration",
@@ -165,7 +252,7 @@ enum ConfigAction {
     Set {
         /// Configuration key to set
         #[arg(
-            help = "The configuration key to set (api_key, auth_method, default_format, use_color, use_cache)"
+            help = "The configuration key to set (api_key, auth_method, default_format, locale, use_color, use_cache, quota_warning_threshold)"
         )]
         key: String,
 
@@ -194,6 +281,9 @@ async fn main() {
     // Run the CLI and handle errors
     if let Err(err) = run(cli).await {
         eprintln!("{} {}", "Error:".bold().red(), err);
+        if matches!(err, CliError::MissingApiKey) {
+            utils::print_env_help();
+        }
         process::exit(1);
     }
 }
@@ -203,27 +293,46 @@ async fn run(cli: Cli) -> Result<(), CliError> {
     let config = Config::load()?;
 
     // Get API key from args, environment, or config
-    let api_key = match cli.api_key {
-        Some(key) => key,
-        None => match env::var("EXCHANGE_RATE_API_KEY") {
-            Ok(key) => key,
-            Err(_) => match &config.api_key {
-                Some(key) => key.clone(),
-                None => return Err(CliError::MissingApiKey),
-            },
-        },
-    };
-
-    if cli.verbose {
-        println!("{} Using Exchange Rate API", "Info:".bold().blue());
-    }
+    let api_key = cli
+        .api_key
+        .or_else(|| env::var("EXCHANGE_RATE_API_KEY").ok())
+        .or_else(|| config.api_key.clone());
+
+    // Create client builder for the provider, falling back to open access without an API key
+    let mut client_builder = match cli.provider.to_lowercase().as_str() {
+        "exchangerate-api" => {
+            if cli.verbose {
+                println!("{} Using Exchange Rate API", "Info:".bold().blue());
+            }
 
-    // Create client builder
-    let mut client_builder = ExchangeRateClient::builder().api_key(api_key);
+            match api_key {
+                Some(api_key) => ExchangeRateClient::builder().api_key(api_key),
+                None => {
+                    eprintln!(
+                        "{} No API key configured, using the free open access API (latest rates only, updated daily). Rates By Exchange Rate API: https://www.exchangerate-api.com",
+                        "Notice:".bold().yellow()
+                    );
+                    ExchangeRateClient::builder().open_access()
+                }
+            }
+        }
+        "ecb" => {
+            if cli.verbose {
+                println!(
+                    "{} Using European Central Bank reference rates",
+                    "Info:".bold().blue()
+                );
+            }
+            ExchangeRateClient::builder().provider(Arc::new(EcbProvider::new()))
+        }
+        _ => return Err(CliError::InvalidProvider(cli.provider)),
+    };
 
-    // Configure auth method from args or config
+    // Configure auth method from args or config (not applicable to open access or the ECB)
     let auth_method_str = cli.auth_method.or_else(|| config.auth_method.clone());
-    if let Some(auth_method_str) = auth_method_str {
+    let uses_api_key =
+        cli.provider.eq_ignore_ascii_case("exchangerate-api") && !client_builder.is_open_access();
+    if let Some(auth_method_str) = auth_method_str.filter(|_| uses_api_key) {
         let auth_method = match auth_method_str.to_lowercase().as_str() {
             "bearer" => {
                 if cli.verbose {
@@ -259,18 +368,47 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             println!("{} Cache disabled", "Info:".bold().blue());
         }
         client_builder = client_builder.disable_cache();
-    } else if cli.verbose {
-        println!("{} Using cache to reduce API calls", "Info:".bold().blue());
+    } else {
+        if cli.verbose {
+            println!("{} Using cache to reduce API calls", "Info:".bold().blue());
+        }
+
+        // Keep responses between runs, so that they're available offline
+        match open_cache() {
+            Ok(cache) => client_builder = client_builder.with_cache(Arc::new(cache)),
+            Err(err) => eprintln!(
+                "{} Could not open the response cache, caching for this run only: {}",
+                "Warning:".bold().yellow(),
+                err
+            ),
+        }
+    }
+
+    if cli.offline {
+        if cli.verbose {
+            println!("{} Offline, using cached data only", "Info:".bold().blue());
+        }
+        client_builder = client_builder.network_policy(NetworkPolicy::Offline);
     }
 
     // Build the client
-    let client = client_builder.build().map_err(|e| CliError::from(e))?;
+    let client = client_builder.build().map_err(CliError::from)?;
 
     // Get output format from args or config
-    let format = cli
-        .format
-        .as_deref()
-        .or_else(|| config.default_format.as_deref());
+    let format = cli.format.as_deref().or(config.default_format.as_deref());
+
+    // Get the locale from args or config, falling back to the environment
+    let locale = match cli.locale.as_deref().or(config.locale.as_deref()) {
+        Some(tag) => Locale::parse(tag).ok_or_else(|| CliError::InvalidLocale(tag.to_string()))?,
+        None => Locale::from_env().unwrap_or_default(),
+    };
+
+    if cli.verbose {
+        println!("{} Using locale {}", "Info:".bold().blue(), locale.tag());
+    }
+
+    // Get the quota warning threshold from args or config
+    let quota_warning = cli.quota_warning.or(config.quota_warning_threshold);
 
     // Execute the requested command
     match &cli.command {
@@ -282,18 +420,20 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                     base_currency
                 );
             }
-            commands::latest::execute(&client, base_currency, format, cli.verbose).await?
+            commands::latest::execute(&client, base_currency, format, &locale, cli.verbose).await?
         }
         Commands::Convert {
             amount,
             from_currency,
             to_currency,
+            rounding,
+            cash,
         } => {
             if cli.verbose {
                 println!(
                     "{} Converting {:.2} {} to {}",
                     "Info:".bold().blue(),
-                    amount,
+                    amount.round_dp(2),
                     from_currency,
                     to_currency
                 );
@@ -303,7 +443,9 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                 *amount,
                 from_currency,
                 to_currency,
+                utils::parse_rounding_policy(rounding.as_deref(), *cash)?,
                 format,
+                &locale,
                 cli.verbose,
             )
             .await?
@@ -311,6 +453,7 @@ async fn run(cli: Cli) -> Result<(), CliError> {
         Commands::Pair {
             from_currency,
             to_currency,
+            details,
         } => {
             if cli.verbose {
                 println!(
@@ -320,8 +463,37 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                     to_currency
                 );
             }
-            commands::pair::execute(&client, from_currency, to_currency, format, cli.verbose)
-                .await?
+            commands::pair::execute(
+                &client,
+                from_currency,
+                to_currency,
+                *details,
+                format,
+                cli.verbose,
+            )
+            .await?
+        }
+        Commands::Portfolio {
+            file,
+            target_currency,
+        } => {
+            if cli.verbose {
+                println!(
+                    "{} Valuing portfolio {} in {}",
+                    "Info:".bold().blue(),
+                    file.display(),
+                    target_currency
+                );
+            }
+            commands::portfolio::execute(
+                &client,
+                file,
+                target_currency,
+                format,
+                &locale,
+                cli.verbose,
+            )
+            .await?
         }
         Commands::Codes => {
             if cli.verbose {
@@ -332,9 +504,20 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             }
             commands::codes::execute(&client, format, cli.verbose).await?
         }
+        Commands::Quota => {
+            commands::quota::execute(&client, format, quota_warning, cli.verbose).await?
+        }
         Commands::Config { action } => handle_config_command(action, &config, cli.verbose)?,
     }
 
+    // Warn if the quota is running low (the quota command reports this itself)
+    if let Some(threshold) = quota_warning
+        && !cli.offline
+        && !matches!(cli.command, Commands::Quota | Commands::Config { .. })
+    {
+        commands::quota::check_threshold(&client, threshold, cli.verbose).await;
+    }
+
     if cli.verbose {
         println!("{} Command completed successfully", "Info:".bold().blue());
     }
@@ -342,6 +525,17 @@ async fn run(cli: Cli) -> Result<(), CliError> {
     Ok(())
 }
 
+/// Open the response cache, creating its directory if needed
+fn open_cache() -> Result<SqliteCache, CliError> {
+    let cache_path = config::get_cache_path()?;
+
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    SqliteCache::new(&cache_path).map_err(|e| CliError::UnexpectedError(e.to_string()))
+}
+
 /// Handle the config command
 fn handle_config_command(
     action: &Option<ConfigAction>,
@@ -370,8 +564,19 @@ fn handle_config_command(
                     .as_deref()
                     .unwrap_or("Not set")
             );
+            println!(
+                "Locale: {}",
+                current_config.locale.as_deref().unwrap_or("Not set")
+            );
             println!("Use Color: {}", current_config.use_color.unwrap_or(true));
             println!("Use Cache: {}", current_config.use_cache.unwrap_or(true));
+            println!(
+                "Quota Warning Threshold: {}",
+                match current_config.quota_warning_threshold {
+                    Some(threshold) => threshold.to_string(),
+                    None => "Not set".to_string(),
+                }
+            );
 
             // Show config file location
             let config_path = config::get_config_path()?;
@@ -412,6 +617,19 @@ fn handle_config_command(
                         )));
                     }
                 },
+                "locale" => match Locale::parse(value) {
+                    Some(locale) => {
+                        new_config.locale = Some(locale.tag().to_string());
+                        println!("Locale set to: {}", locale.tag());
+                    }
+                    None => {
+                        return Err(CliError::InvalidConfigValue(format!(
+                            "Invalid locale: {}. Supported locales are: {}.",
+                            value,
+                            Locale::supported().collect::<Vec<_>>().join(", ")
+                        )));
+                    }
+                },
                 "use_color" => match value.to_lowercase().as_str() {
                     "true" | "yes" | "1" => {
                         new_config.use_color = Some(true);
@@ -444,9 +662,27 @@ fn handle_config_command(
                         )));
                     }
                 },
+                "quota_warning_threshold" => match value.to_lowercase().as_str() {
+                    "none" | "off" => {
+                        new_config.quota_warning_threshold = None;
+                        println!("Quota warning disabled");
+                    }
+                    threshold => match threshold.parse::<u64>() {
+                        Ok(threshold) => {
+                            new_config.quota_warning_threshold = Some(threshold);
+                            println!("Quota warning threshold set to: {}", threshold);
+                        }
+                        Err(_) => {
+                            return Err(CliError::InvalidConfigValue(format!(
+                                "Invalid quota warning threshold: {}. Use a number of requests or 'off'.",
+                                value
+                            )));
+                        }
+                    },
+                },
                 _ => {
                     return Err(CliError::InvalidConfigKey(format!(
-                        "Invalid configuration key: {}. Valid keys are 'api_key', 'auth_method', 'default_format', 'use_color', 'use_cache'.",
+                        "Invalid configuration key: {}. Valid keys are 'api_key', 'auth_method', 'default_format', 'locale', 'use_color', 'use_cache', 'quota_warning_threshold'.",
                         key
                     )));
                 }
@@ -463,11 +699,8 @@ fn handle_config_command(
             }
         }
         Some(ConfigAction::Reset) => {
-            // Create a new default configuration
-            let new_config = Config::default();
-
-            // Save the default configuration
-            new_config.save()?;
+            // Save a new default configuration
+            config::create_default_config()?;
 
             println!("Configuration reset to defaults");
 
@@ -39,8 +39,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Get direct pair conversion rate
     println!("\nGetting direct pair conversion rate from GBP to JPY...");
-    let rate = client.get_pair_conversion("GBP", "JPY").await?;
-    println!("1 GBP = {rate:.4} JPY");
+    let pair = client.get_pair_conversion("GBP", "JPY").await?;
+    println!("1 GBP = {:.4} JPY", pair.conversion_rate);
+    println!("Next update: {}", pair.time_next_update_utc);
 
     // Get supported currencies
     println!("\nFetching supported currencies...");
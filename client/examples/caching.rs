@@ -39,16 +39,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Try pair conversion (should use a different cache key)
     println!("\nPair conversion request:");
     let start = std::time::Instant::now();
-    let rate = client.get_pair_conversion("USD", "EUR").await?;
+    let pair = client.get_pair_conversion("USD", "EUR").await?;
     println!("Request took: {:?}", start.elapsed());
-    println!("USD to EUR direct rate: {}", rate);
+    println!("USD to EUR direct rate: {}", pair.conversion_rate);
 
     // Second pair conversion (should be cached)
     println!("\nSecond pair conversion request (should be cached):");
     let start = std::time::Instant::now();
-    let rate = client.get_pair_conversion("USD", "EUR").await?;
+    let pair = client.get_pair_conversion("USD", "EUR").await?;
     println!("Request took: {:?}", start.elapsed());
-    println!("USD to EUR direct rate: {}", rate);
+    println!("USD to EUR direct rate: {}", pair.conversion_rate);
 
     Ok(())
 }
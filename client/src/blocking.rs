@@ -0,0 +1,513 @@
+//! A synchronous client for programs without an async runtime
+//!
+//! [`ExchangeRateClient`] has the same builder and methods as the async
+//! [`crate::ExchangeRateClient`], and shares its models, caches and errors. It runs
+//! the async client on a small runtime of its own, so callers don't need to depend
+//! on Tokio.
+//!
+//! The blocking client must not be used, or dropped, inside an async runtime, where
+//! it would block the runtime's threads. Use the async client there instead.
+
+#[cfg(feature = "decimal")]
+use crate::Decimal;
+use crate::{
+    AuthMethod, CacheBackend, CacheConfig, CurrencyCode, EnrichedPairResponse, ExchangeRateError,
+    ExchangeRateResponse, HistoricalRatesResponse, HttpTransport, IntoCurrencyCode, NetworkPolicy,
+    PairConversionResponse, Portfolio, QuotaResponse, RateLimit, RateProvider, RateTable,
+    RequestBudget, RetryPolicy, RoundingPolicy, Valuation,
+};
+use chrono::NaiveDate;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Builder for creating a blocking `ExchangeRateClient` with custom configuration
+///
+/// Takes the same options as [`crate::ExchangeRateClientBuilder`].
+#[derive(Default)]
+pub struct ExchangeRateClientBuilder {
+    inner: crate::ExchangeRateClientBuilder,
+}
+
+impl ExchangeRateClientBuilder {
+    /// Create a new builder with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the API key for authentication
+    #[must_use]
+    pub fn api_key(self, api_key: impl Into<String>) -> Self {
+        self.map(|inner| inner.api_key(api_key))
+    }
+
+    /// Set the authentication method
+    #[must_use]
+    pub fn auth_method(self, auth_method: AuthMethod) -> Self {
+        self.map(|inner| inner.auth_method(auth_method))
+    }
+
+    /// Use the free, keyless open access endpoint
+    #[must_use]
+    pub fn open_access(self) -> Self {
+        self.map(crate::ExchangeRateClientBuilder::open_access)
+    }
+
+    /// Check if the builder is configured for the keyless open access endpoint
+    #[must_use]
+    pub const fn is_open_access(&self) -> bool {
+        self.inner.is_open_access()
+    }
+
+    /// Set a custom base URL (useful for testing or if the API URL changes)
+    #[must_use]
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        self.map(|inner| inner.base_url(base_url))
+    }
+
+    /// Set a custom timeout for HTTP requests
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.map(|inner| inner.timeout(timeout))
+    }
+
+    /// Send requests through a custom transport instead of `reqwest`
+    #[must_use]
+    pub fn transport(self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.map(|inner| inner.transport(transport))
+    }
+
+    /// Serve rates from another provider instead of exchangerate-api.com
+    #[must_use]
+    pub fn provider(self, provider: Arc<dyn RateProvider>) -> Self {
+        self.map(|inner| inner.provider(provider))
+    }
+
+    /// Set a cache backend for the client
+    #[must_use]
+    pub fn with_cache(self, cache: Arc<dyn CacheBackend>) -> Self {
+        self.map(|inner| inner.with_cache(cache))
+    }
+
+    /// Set cache configuration options
+    #[must_use]
+    pub fn cache_config(self, config: CacheConfig) -> Self {
+        self.map(|inner| inner.cache_config(config))
+    }
+
+    /// Disable caching
+    #[must_use]
+    pub fn disable_cache(self) -> Self {
+        self.map(crate::ExchangeRateClientBuilder::disable_cache)
+    }
+
+    /// Round amounts returned by `convert` and `convert_decimal` with this policy
+    #[must_use]
+    pub fn rounding(self, policy: RoundingPolicy) -> Self {
+        self.map(|inner| inner.rounding(policy))
+    }
+
+    /// Serve every base currency from the latest rates of a single base
+    #[must_use]
+    pub fn single_base(self, base: CurrencyCode) -> Self {
+        self.map(|inner| inner.single_base(base))
+    }
+
+    /// Retry failed requests according to a policy
+    #[must_use]
+    pub fn retry_policy(self, policy: RetryPolicy) -> Self {
+        self.map(|inner| inner.retry_policy(policy))
+    }
+
+    /// Limit how fast requests are sent to the API
+    #[must_use]
+    pub fn rate_limit(self, limit: RateLimit) -> Self {
+        self.map(|inner| inner.rate_limit(limit))
+    }
+
+    /// Limit the number of requests sent to the API each month
+    #[must_use]
+    pub fn request_budget(self, budget: RequestBudget) -> Self {
+        self.map(|inner| inner.request_budget(budget))
+    }
+
+    /// Choose when the client may call the API
+    #[must_use]
+    pub fn network_policy(self, policy: NetworkPolicy) -> Self {
+        self.map(|inner| inner.network_policy(policy))
+    }
+
+    /// Build the client
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid, or `TransportError` if the
+    /// client's runtime can't be started
+    pub fn build(self) -> Result<ExchangeRateClient, ExchangeRateError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("exchangerate-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                ExchangeRateError::TransportError(format!("Failed to start runtime: {}", e))
+            })?;
+
+        Ok(ExchangeRateClient {
+            inner: self.inner.build()?,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    fn map(
+        self,
+        configure: impl FnOnce(crate::ExchangeRateClientBuilder) -> crate::ExchangeRateClientBuilder,
+    ) -> Self {
+        Self {
+            inner: configure(self.inner),
+        }
+    }
+}
+
+impl From<crate::ExchangeRateClientBuilder> for ExchangeRateClientBuilder {
+    fn from(inner: crate::ExchangeRateClientBuilder) -> Self {
+        Self { inner }
+    }
+}
+
+/// A blocking client for the Exchange Rate API
+///
+/// Each method blocks until the async client's method of the same name completes.
+/// Clones share the cache and the runtime, whose one worker thread also runs
+/// background cache refreshes.
+///
+/// # Panics
+///
+/// Methods panic if called inside an async runtime, and dropping the last clone
+/// there panics too.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::blocking::ExchangeRateClient;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = ExchangeRateClient::builder()
+///         .api_key("your-api-key")
+///         .build()?;
+///
+///     let amount = client.convert(100.0, "USD", "EUR")?;
+///     println!("100 USD = {:.2} EUR", amount);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ExchangeRateClient {
+    inner: crate::ExchangeRateClient,
+    runtime: Arc<Runtime>,
+}
+
+impl ExchangeRateClient {
+    /// Creates a new client builder
+    #[must_use]
+    pub fn builder() -> ExchangeRateClientBuilder {
+        ExchangeRateClientBuilder::new()
+    }
+
+    /// Check if the client is using the keyless open access endpoint
+    #[must_use]
+    pub fn is_open_access(&self) -> bool {
+        self.inner.is_open_access()
+    }
+
+    /// Get the number of requests left in this month's budget, or `None` without one
+    pub fn remaining_budget(&self) -> Option<u64> {
+        self.block_on(self.inner.remaining_budget())
+    }
+
+    /// Get latest exchange rates for a base currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub fn get_latest_rates(
+        &self,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_latest_rates(base_code))
+    }
+
+    /// Get latest exchange rates for a base currency as a [`RateTable`] of cross rates
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub fn get_rate_table(
+        &self,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<RateTable, ExchangeRateError> {
+        self.block_on(self.inner.get_rate_table(base_code))
+    }
+
+    /// Convert an amount from one currency to another
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// the API returns an error response, or if the target currency is not supported
+    pub fn convert(
+        &self,
+        amount: f64,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Result<f64, ExchangeRateError> {
+        self.block_on(self.inner.convert(amount, from_currency, to_currency))
+    }
+
+    /// Get latest exchange rates for a base currency as exact decimals
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    #[cfg(feature = "decimal")]
+    pub fn get_latest_rates_decimal(
+        &self,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<ExchangeRateResponse<Decimal>, ExchangeRateError> {
+        self.block_on(self.inner.get_latest_rates_decimal(base_code))
+    }
+
+    /// Convert an amount from one currency to another using exact decimal arithmetic
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// the API returns an error response, or if the target currency is not supported
+    #[cfg(feature = "decimal")]
+    pub fn convert_decimal(
+        &self,
+        amount: Decimal,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Result<Decimal, ExchangeRateError> {
+        self.block_on(
+            self.inner
+                .convert_decimal(amount, from_currency, to_currency),
+        )
+    }
+
+    /// Get pair conversion rate (direct conversion between two currencies)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub fn get_pair_conversion(
+        &self,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_pair_conversion(from_currency, to_currency))
+    }
+
+    /// Get pair conversion rate along with `amount` converted into the target currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub fn get_pair_conversion_with_amount(
+        &self,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+        amount: f64,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_pair_conversion_with_amount(
+            from_currency,
+            to_currency,
+            amount,
+        ))
+    }
+
+    /// Get the conversion rate between two currencies along with metadata about
+    /// the target currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (enriched data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub fn get_enriched_pair(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        target_code: impl IntoCurrencyCode,
+    ) -> Result<EnrichedPairResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_enriched_pair(base_code, target_code))
+    }
+
+    /// Get historical exchange rates for a base currency on a specific date
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (historical data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub fn get_historical_rates(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_historical_rates(base_code, date))
+    }
+
+    /// Get historical exchange rates for a base currency on a specific date,
+    /// with every rate multiplied by `amount`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (historical data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub fn get_historical_rates_with_amount(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        date: NaiveDate,
+        amount: f64,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        self.block_on(
+            self.inner
+                .get_historical_rates_with_amount(base_code, date, amount),
+        )
+    }
+
+    /// Get the request quota for the API key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response.
+    /// Returns `MissingApiKey` when using open access.
+    pub fn get_quota(&self) -> Result<QuotaResponse, ExchangeRateError> {
+        self.block_on(self.inner.get_quota())
+    }
+
+    /// Get supported currency codes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response.
+    /// Returns `MissingApiKey` when using open access.
+    pub fn get_supported_codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        self.block_on(self.inner.get_supported_codes())
+    }
+
+    /// Value every position of a portfolio in a reporting currency
+    ///
+    /// The blocking equivalent of [`Portfolio::value_in`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reporting currency is invalid, the API request fails,
+    /// or the API has no rate for one of the positions' currencies
+    pub fn value_portfolio(
+        &self,
+        portfolio: &Portfolio,
+        target: impl IntoCurrencyCode,
+    ) -> Result<Valuation, ExchangeRateError> {
+        self.block_on(portfolio.value_in(&self.inner, target))
+    }
+
+    /// Value every position of a portfolio in a reporting currency using exact
+    /// decimal arithmetic
+    ///
+    /// The blocking equivalent of [`Portfolio::value_in`] for a `Portfolio<Decimal>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reporting currency is invalid, the API request fails,
+    /// the API has no rate for one of the positions' currencies, or a value overflows
+    #[cfg(feature = "decimal")]
+    pub fn value_portfolio_decimal(
+        &self,
+        portfolio: &Portfolio<Decimal>,
+        target: impl IntoCurrencyCode,
+    ) -> Result<Valuation<Decimal>, ExchangeRateError> {
+        self.block_on(portfolio.value_in(&self.inner, target))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{LATEST_USD, fake_builder};
+    use crate::{FakeTransport, HttpResponse};
+    use reqwest::StatusCode;
+
+    fn fake_client(transport: &Arc<FakeTransport>) -> ExchangeRateClient {
+        ExchangeRateClientBuilder::from(fake_builder(transport, AuthMethod::BearerToken))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_blocking_requests_are_cached() {
+        let transport = Arc::new(FakeTransport::new());
+        transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+        let client = fake_client(&transport);
+
+        let rates = client.get_latest_rates("USD").unwrap();
+        assert_eq!(rates.get_rate("EUR"), Some(0.8));
+        assert_eq!(client.convert(100.0, "USD", "EUR").unwrap(), 80.0);
+
+        let portfolio: Portfolio = [("EUR".parse().unwrap(), 8.0)].into_iter().collect();
+        let valuation = client.value_portfolio(&portfolio, "USD").unwrap();
+        assert!((valuation.total - 10.0).abs() < 1e-9);
+
+        // Clones share the cache
+        client.clone().get_rate_table("USD").unwrap();
+        assert_eq!(transport.request_count(), 1);
+    }
+
+    #[test]
+    fn test_blocking_errors() {
+        let transport = Arc::new(FakeTransport::new());
+        let client = fake_client(&transport);
+
+        assert!(matches!(
+            client.get_latest_rates("EURO"),
+            Err(ExchangeRateError::InvalidCurrencyCode(_))
+        ));
+        // Nothing is registered with the transport
+        assert!(matches!(
+            client.get_pair_conversion("USD", "GBP"),
+            Err(ExchangeRateError::TransportError(_))
+        ));
+
+        // Requests go through reqwest on the client's own runtime by default. The
+        // port is out of range, so reqwest fails without opening a connection
+        let open = ExchangeRateClient::builder()
+            .open_access()
+            .base_url("http://localhost:99999")
+            .build()
+            .unwrap();
+        assert!(open.is_open_access());
+        assert!(matches!(
+            open.get_quota(),
+            Err(ExchangeRateError::MissingApiKey)
+        ));
+        assert!(matches!(
+            open.get_latest_rates("USD"),
+            Err(ExchangeRateError::HttpClientError(_))
+        ));
+    }
+}
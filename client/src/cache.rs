@@ -1,13 +1,18 @@
-use crate::models::ExchangeRateResponse;
+use crate::models::{
+    EnrichedPairResponse, ExchangeRateResponse, HistoricalRatesResponse, PairConversionResponse,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use thiserror::Error;
 
 /// Errors that can occur when working with the cache
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum CacheError {
     /// The requested item was not found in the cache
     #[error("Item not found in cache")]
@@ -59,15 +64,7 @@ impl CachedResponse<ExchangeRateResponse> {
     #[must_use]
     pub fn new_with_api_expiration(response: ExchangeRateResponse) -> Self {
         let cached_at = Utc::now();
-
-        // Use the time_next_update_unix from the response as the expiration time
-        // If it's not available, cache for 24 hours by default
-        let expires_at = if response.time_next_update_unix > 0 {
-            DateTime::from_timestamp(response.time_next_update_unix as i64, 0)
-                .unwrap_or_else(|| cached_at + Duration::hours(24))
-        } else {
-            cached_at + Duration::hours(24)
-        };
+        let expires_at = api_expiration(response.time_next_update_unix, cached_at);
 
         Self {
             response,
@@ -93,6 +90,18 @@ pub struct CacheConfig {
 
     /// Default TTL for cached responses if not specified by the API
     pub default_ttl: Duration,
+
+    /// How long past its expiry an entry may still be returned while a fresh copy
+    /// is fetched in the background
+    ///
+    /// With `None` (the default), expired entries are refreshed before returning.
+    pub stale_while_revalidate: Option<Duration>,
+
+    /// How long past its expiry an entry may be returned in place of an error when
+    /// the API can't be reached or fails with a transient error
+    ///
+    /// With `None` (the default), such errors are returned.
+    pub stale_if_error: Option<Duration>,
 }
 
 impl Default for CacheConfig {
@@ -100,10 +109,123 @@ impl Default for CacheConfig {
         Self {
             enabled: true,
             default_ttl: Duration::hours(24),
+            stale_while_revalidate: None,
+            stale_if_error: None,
         }
     }
 }
 
+impl CacheConfig {
+    /// Check if an entry that expired at `expires_at` is within a staleness window
+    pub(crate) fn is_within(window: Option<Duration>, expires_at: DateTime<Utc>) -> bool {
+        window.is_some_and(|window| Utc::now() <= expires_at + window)
+    }
+}
+
+/// A response type the client caches
+#[async_trait]
+pub(crate) trait Cacheable:
+    Serialize + DeserializeOwned + Clone + Send + Sync + 'static
+{
+    /// When a response fetched now should expire
+    fn expires_at(&self) -> DateTime<Utc>;
+
+    /// Mark the response as served from an expired cache entry
+    fn mark_stale(&mut self) {}
+
+    /// The response, marked as served from an expired cache entry
+    fn into_stale(mut self) -> Self {
+        self.mark_stale();
+        self
+    }
+
+    /// Read an entry, including an expired one, with its expiration time
+    async fn read(
+        cache: &dyn CacheBackend,
+        key: &str,
+    ) -> Result<(Self, DateTime<Utc>), CacheError> {
+        let (json, _, expires_at) = cache.get_stale_raw(key).await?;
+        let response =
+            serde_json::from_str(&json).map_err(|e| CacheError::Serialization(e.to_string()))?;
+
+        Ok((response, expires_at))
+    }
+
+    /// Store an entry
+    async fn write(&self, cache: &dyn CacheBackend, key: &str) -> Result<(), CacheError> {
+        let json =
+            serde_json::to_string(self).map_err(|e| CacheError::Serialization(e.to_string()))?;
+
+        cache
+            .set_raw(key, json, Utc::now(), self.expires_at())
+            .await
+    }
+}
+
+/// Latest rates are stored as `CachedResponse`s, for compatibility with existing caches
+#[async_trait]
+impl Cacheable for ExchangeRateResponse {
+    fn expires_at(&self) -> DateTime<Utc> {
+        api_expiration(self.time_next_update_unix, Utc::now())
+    }
+
+    fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    async fn read(
+        cache: &dyn CacheBackend,
+        key: &str,
+    ) -> Result<(Self, DateTime<Utc>), CacheError> {
+        let cached = cache.get_stale_exchange_rate(key).await?;
+        Ok((cached.response, cached.expires_at))
+    }
+
+    async fn write(&self, cache: &dyn CacheBackend, key: &str) -> Result<(), CacheError> {
+        cache
+            .set_exchange_rate(key, CachedResponse::new_with_api_expiration(self.clone()))
+            .await
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Cacheable for ExchangeRateResponse<Decimal> {
+    fn expires_at(&self) -> DateTime<Utc> {
+        api_expiration(self.time_next_update_unix, Utc::now())
+    }
+
+    fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+}
+
+impl Cacheable for PairConversionResponse {
+    fn expires_at(&self) -> DateTime<Utc> {
+        api_expiration(self.time_next_update_unix, Utc::now())
+    }
+
+    fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+}
+
+impl Cacheable for EnrichedPairResponse {
+    fn expires_at(&self) -> DateTime<Utc> {
+        api_expiration(self.time_next_update_unix, Utc::now())
+    }
+
+    fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+}
+
+/// Rates for a past date are final, so the entry never expires
+impl Cacheable for HistoricalRatesResponse {
+    fn expires_at(&self) -> DateTime<Utc> {
+        never_expires()
+    }
+}
+
 /// Trait for cache backends
 #[async_trait]
 pub trait CacheBackend: Send + Sync {
@@ -135,6 +257,28 @@ pub trait CacheBackend: Send + Sync {
         expires_at: DateTime<Utc>,
     ) -> Result<(), CacheError>;
 
+    /// Get a cached response by key for ExchangeRateResponse, even if it has expired
+    ///
+    /// Used to serve stale data when the API can't be called. Backends that drop
+    /// expired entries can rely on the default, which only returns fresh entries.
+    async fn get_stale_exchange_rate(
+        &self,
+        key: &str,
+    ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+        self.get_exchange_rate(key).await
+    }
+
+    /// Get a cached response as raw JSON string, even if it has expired
+    ///
+    /// Used to serve stale data when the API can't be called. Backends that drop
+    /// expired entries can rely on the default, which only returns fresh entries.
+    async fn get_stale_raw(
+        &self,
+        key: &str,
+    ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
+        self.get_raw(key).await
+    }
+
     /// Invalidate a cached response
     async fn invalidate(&self, key: &str) -> Result<(), CacheError>;
 
@@ -158,15 +302,13 @@ impl InMemoryCache {
             expiry: Arc::new(RwLock::new(HashMap::new())),
         }
     }
-}
 
-#[async_trait]
-impl CacheBackend for InMemoryCache {
-    async fn get_exchange_rate(
+    /// Read an entry's JSON and expiration time
+    fn read_entry(
         &self,
         key: &str,
-    ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
-        // Check if the key exists and is not expired
+        allow_expired: bool,
+    ) -> Result<(String, DateTime<Utc>), CacheError> {
         let cache = self
             .cache
             .read()
@@ -180,15 +322,33 @@ impl CacheBackend for InMemoryCache {
         let expires_at = expiry.get(key).ok_or(CacheError::NotFound)?;
 
         // Check if expired
-        if Utc::now() > *expires_at {
+        if !allow_expired && Utc::now() > *expires_at {
             return Err(CacheError::Expired);
         }
 
+        Ok((json.clone(), *expires_at))
+    }
+}
+
+#[async_trait]
+impl CacheBackend for InMemoryCache {
+    async fn get_exchange_rate(
+        &self,
+        key: &str,
+    ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+        let (json, _) = self.read_entry(key, false)?;
+
         // Deserialize the cached response
-        let cached_response: CachedResponse<ExchangeRateResponse> =
-            serde_json::from_str(json).map_err(|e| CacheError::Serialization(e.to_string()))?;
+        serde_json::from_str(&json).map_err(|e| CacheError::Serialization(e.to_string()))
+    }
 
-        Ok(cached_response)
+    async fn get_stale_exchange_rate(
+        &self,
+        key: &str,
+    ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+        let (json, _) = self.read_entry(key, true)?;
+
+        serde_json::from_str(&json).map_err(|e| CacheError::Serialization(e.to_string()))
     }
 
     async fn set_exchange_rate(
@@ -220,28 +380,19 @@ impl CacheBackend for InMemoryCache {
         &self,
         key: &str,
     ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
-        // Check if the key exists and is not expired
-        let cache = self
-            .cache
-            .read()
-            .map_err(|e| CacheError::Backend(e.to_string()))?;
-        let expiry = self
-            .expiry
-            .read()
-            .map_err(|e| CacheError::Backend(e.to_string()))?;
+        let (json, expires_at) = self.read_entry(key, false)?;
 
-        let json = cache.get(key).ok_or(CacheError::NotFound)?;
-        let expires_at = expiry.get(key).ok_or(CacheError::NotFound)?;
-
-        // Check if expired
-        if Utc::now() > *expires_at {
-            return Err(CacheError::Expired);
-        }
+        // The cached_at time isn't stored, so report the current time
+        Ok((json, Utc::now(), expires_at))
+    }
 
-        // Get the cached_at time from the JSON
-        let cached_at = Utc::now(); // Fallback value
+    async fn get_stale_raw(
+        &self,
+        key: &str,
+    ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
+        let (json, expires_at) = self.read_entry(key, true)?;
 
-        Ok((json.clone(), cached_at, *expires_at))
+        Ok((json, Utc::now(), expires_at))
     }
 
     async fn set_raw(
@@ -336,14 +487,13 @@ pub mod sqlite {
                 conn: Arc::new(Mutex::new(conn)),
             })
         }
-    }
 
-    #[async_trait]
-    impl CacheBackend for SqliteCache {
-        async fn get_exchange_rate(
+        /// Read an entry's JSON and timestamps
+        fn read_row(
             &self,
             key: &str,
-        ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+            allow_expired: bool,
+        ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
             let conn = self
                 .conn
                 .lock()
@@ -370,10 +520,6 @@ pub mod sqlite {
 
             match result {
                 Ok(Some((response_json, cached_at_str, expires_at_str))) => {
-                    // Parse the response JSON
-                    let response: ExchangeRateResponse = serde_json::from_str(&response_json)
-                        .map_err(|e| CacheError::Serialization(e.to_string()))?;
-
                     // Parse the timestamps
                     let cached_at = DateTime::parse_from_rfc3339(&cached_at_str)
                         .map_err(|e| CacheError::Serialization(e.to_string()))?
@@ -383,22 +529,52 @@ pub mod sqlite {
                         .map_err(|e| CacheError::Serialization(e.to_string()))?
                         .with_timezone(&Utc);
 
-                    let cached = CachedResponse {
-                        response,
-                        cached_at,
-                        expires_at,
-                    };
-
-                    if Utc::now() > expires_at {
+                    if !allow_expired && Utc::now() > expires_at {
                         return Err(CacheError::Expired);
                     }
 
-                    Ok(cached)
+                    Ok((response_json, cached_at, expires_at))
                 }
                 Ok(None) => Err(CacheError::NotFound),
                 Err(e) => Err(CacheError::Backend(e.to_string())),
             }
         }
+    }
+
+    #[async_trait]
+    impl CacheBackend for SqliteCache {
+        async fn get_exchange_rate(
+            &self,
+            key: &str,
+        ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+            let (response_json, cached_at, expires_at) = self.read_row(key, false)?;
+
+            // Parse the response JSON
+            let response: ExchangeRateResponse = serde_json::from_str(&response_json)
+                .map_err(|e| CacheError::Serialization(e.to_string()))?;
+
+            Ok(CachedResponse {
+                response,
+                cached_at,
+                expires_at,
+            })
+        }
+
+        async fn get_stale_exchange_rate(
+            &self,
+            key: &str,
+        ) -> Result<CachedResponse<ExchangeRateResponse>, CacheError> {
+            let (response_json, cached_at, expires_at) = self.read_row(key, true)?;
+
+            let response: ExchangeRateResponse = serde_json::from_str(&response_json)
+                .map_err(|e| CacheError::Serialization(e.to_string()))?;
+
+            Ok(CachedResponse {
+                response,
+                cached_at,
+                expires_at,
+            })
+        }
 
         async fn set_exchange_rate(
             &self,
@@ -430,50 +606,14 @@ pub mod sqlite {
             &self,
             key: &str,
         ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
-            let conn = self
-                .conn
-                .lock()
-                .map_err(|e| CacheError::Backend(e.to_string()))?;
-
-            let mut stmt = conn
-                .prepare(
-                    "SELECT response, cached_at, expires_at FROM exchange_rate_cache WHERE key = ?",
-                )
-                .map_err(|e| CacheError::Backend(e.to_string()))?;
-
-            let result: SqliteResult<Option<(String, String, String)>> = stmt
-                .query_row(params![key], |row| {
-                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-                })
-                .map(Some)
-                .or_else(|e| {
-                    if e == rusqlite::Error::QueryReturnedNoRows {
-                        Ok(None)
-                    } else {
-                        Err(e)
-                    }
-                });
-
-            match result {
-                Ok(Some((response_json, cached_at_str, expires_at_str))) => {
-                    // Parse the timestamps
-                    let cached_at = DateTime::parse_from_rfc3339(&cached_at_str)
-                        .map_err(|e| CacheError::Serialization(e.to_string()))?
-                        .with_timezone(&Utc);
-
-                    let expires_at = DateTime::parse_from_rfc3339(&expires_at_str)
-                        .map_err(|e| CacheError::Serialization(e.to_string()))?
-                        .with_timezone(&Utc);
-
-                    if Utc::now() > expires_at {
-                        return Err(CacheError::Expired);
-                    }
+            self.read_row(key, false)
+        }
 
-                    Ok((response_json, cached_at, expires_at))
-                }
-                Ok(None) => Err(CacheError::NotFound),
-                Err(e) => Err(CacheError::Backend(e.to_string())),
-            }
+        async fn get_stale_raw(
+            &self,
+            key: &str,
+        ) -> Result<(String, DateTime<Utc>, DateTime<Utc>), CacheError> {
+            self.read_row(key, true)
         }
 
         async fn set_raw(
@@ -530,6 +670,32 @@ pub mod sqlite {
     }
 }
 
+/// Expiration time for a response based on the API's `time_next_update_unix`
+///
+/// If it's not available, responses are cached for 24 hours by default.
+#[must_use]
+pub(crate) fn api_expiration(
+    time_next_update_unix: u64,
+    cached_at: DateTime<Utc>,
+) -> DateTime<Utc> {
+    if time_next_update_unix > 0 {
+        DateTime::from_timestamp(time_next_update_unix as i64, 0)
+            .unwrap_or_else(|| cached_at + Duration::hours(24))
+    } else {
+        cached_at + Duration::hours(24)
+    }
+}
+
+/// Expiration time for entries whose data can never change, such as historical rates
+///
+/// A far-future date is used rather than `DateTime::MAX_UTC` so that it round-trips
+/// through RFC 3339 in the SQLite backend.
+#[must_use]
+pub(crate) fn never_expires() -> DateTime<Utc> {
+    // 9999-12-31T23:59:59Z
+    DateTime::from_timestamp(253_402_300_799, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
 /// Create a cache key for a request
 #[must_use]
 pub fn create_cache_key(endpoint: &str, params: &[&str]) -> String {
@@ -0,0 +1,456 @@
+//! Consensus rates aggregated across several providers
+
+use crate::{CurrencyCode, ExchangeRateError, ExchangeRateResponse, RateProvider};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::panic::resume_unwind;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// How a [`ConsensusProvider`] combines the rates quoted for a currency
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConsensusMethod {
+    /// The middle rate, or the mean of the two middle rates
+    #[default]
+    Median,
+
+    /// The mean after dropping this fraction of the rates from each end, e.g.
+    /// `0.25` drops the lowest and highest quarter
+    ///
+    /// Falls back to the median if nothing would be left.
+    TrimmedMean(f64),
+}
+
+impl ConsensusMethod {
+    /// Combine rates sorted in ascending order
+    fn combine(self, sorted: &[f64]) -> f64 {
+        match self {
+            Self::Median => median(sorted),
+            Self::TrimmedMean(fraction) => {
+                let trim = (sorted.len() as f64 * fraction.clamp(0.0, 0.5)).floor() as usize;
+                match sorted.get(trim..sorted.len().saturating_sub(trim)) {
+                    Some(kept) if !kept.is_empty() => kept.iter().sum::<f64>() / kept.len() as f64,
+                    _ => median(sorted),
+                }
+            }
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+/// How the rates of a [`ConsensusProvider`] response were agreed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Consensus {
+    /// The providers that answered, in the order they were configured
+    pub providers: Vec<String>,
+
+    /// How the rate for each currency was agreed
+    pub currencies: HashMap<CurrencyCode, CurrencyConsensus>,
+}
+
+impl Consensus {
+    /// The currencies whose providers disagreed by more than the tolerance, sorted by code
+    #[must_use]
+    pub fn disputed(&self) -> Vec<CurrencyCode> {
+        let mut disputed: Vec<_> = self
+            .currencies
+            .iter()
+            .filter(|(_, currency)| currency.disputed)
+            .map(|(code, _)| *code)
+            .collect();
+        disputed.sort();
+        disputed
+    }
+}
+
+/// How the rate for one currency was agreed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyConsensus {
+    /// The providers that quoted a rate for the currency
+    pub providers: Vec<String>,
+
+    /// The lowest rate quoted
+    pub low: f64,
+
+    /// The highest rate quoted
+    pub high: f64,
+
+    /// The difference between the highest and lowest rates, relative to the agreed rate
+    pub spread: f64,
+
+    /// Whether the spread is more than the provider's tolerance
+    pub disputed: bool,
+}
+
+/// A provider that asks several providers at once and agrees a rate for each currency
+///
+/// Each currency's rate is the median, or a trimmed mean, of the rates quoted for
+/// it, so that one faulty provider can't skew it. Responses record how each rate
+/// was agreed in [`ExchangeRateResponse::consensus`]: the providers that quoted
+/// it, the spread of their rates, and whether that spread is more than the
+/// tolerance.
+///
+/// Providers that fail are left out. If fewer than the quorum answer, the first
+/// error in provider order is returned, and currencies quoted by fewer than the
+/// quorum are left out of the response. The response's update times are the
+/// earliest of the answers, so it is cached until the first provider updates.
+///
+/// Pairs are derived from the latest rates and codes come from the first provider
+/// that answers. Historical rates aren't aggregated.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{ConsensusMethod, ConsensusProvider, ExchangeRateClient};
+/// use std::sync::Arc;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let paid = ExchangeRateClient::builder().api_key("your-api-key").build()?;
+///     let open = ExchangeRateClient::builder().open_access().build()?;
+///
+///     // Add more providers, e.g. `EcbProvider`, so that a faulty one is outvoted
+///     let consensus = ConsensusProvider::new(vec![Arc::new(paid), Arc::new(open)])
+///     .method(ConsensusMethod::Median)
+///     .tolerance(0.002)
+///     .quorum(2);
+///
+///     let client = ExchangeRateClient::builder()
+///         .provider(Arc::new(consensus))
+///         .build()?;
+///
+///     let rates = client.get_latest_rates("USD").await?;
+///     if let Some(consensus) = &rates.consensus {
+///         println!("Providers disagree on {:?}", consensus.disputed());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct ConsensusProvider {
+    providers: Vec<Arc<dyn RateProvider>>,
+    method: ConsensusMethod,
+    tolerance: f64,
+    quorum: usize,
+}
+
+impl ConsensusProvider {
+    /// Agree rates across `providers` by their median
+    ///
+    /// Currencies whose rates differ by more than 0.5% are flagged as disputed,
+    /// and a single answer is enough.
+    #[must_use]
+    pub fn new(providers: Vec<Arc<dyn RateProvider>>) -> Self {
+        Self {
+            providers,
+            method: ConsensusMethod::Median,
+            tolerance: 0.005,
+            quorum: 1,
+        }
+    }
+
+    /// Set how the rates quoted for a currency are combined
+    #[must_use]
+    pub const fn method(mut self, method: ConsensusMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set the largest spread between providers' rates, relative to the agreed rate,
+    /// that isn't flagged as disputed, e.g. `0.01` for 1%
+    #[must_use]
+    pub const fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set how many providers must answer, and quote each currency
+    #[must_use]
+    pub fn quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum.max(1);
+        self
+    }
+
+    /// The providers, in the order they were configured
+    #[must_use]
+    pub fn providers(&self) -> &[Arc<dyn RateProvider>] {
+        &self.providers
+    }
+
+    /// Ask every provider for its latest rates at once, in provider order
+    async fn answers(
+        &self,
+        base: CurrencyCode,
+    ) -> Result<Vec<(&str, ExchangeRateResponse)>, ExchangeRateError> {
+        let mut requests = JoinSet::new();
+        for (index, provider) in self.providers.iter().enumerate() {
+            let provider = provider.clone();
+            requests.spawn(async move {
+                let rates = provider.latest(base).await.and_then(|rates| {
+                    if rates.base_code == base {
+                        Ok(rates)
+                    } else {
+                        rates.rebase(base).ok_or(ExchangeRateError::UnsupportedCode)
+                    }
+                });
+                (index, rates)
+            });
+        }
+
+        let mut results = Vec::with_capacity(self.providers.len());
+        while let Some(joined) = requests.join_next().await {
+            match joined {
+                Ok(result) => results.push(result),
+                Err(err) if err.is_panic() => resume_unwind(err.into_panic()),
+                Err(_) => {}
+            }
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        let mut answers = Vec::with_capacity(results.len());
+        let mut first_error = None;
+        for (index, result) in results {
+            match result {
+                Ok(rates) => answers.push((self.providers[index].name(), rates)),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        if answers.len() < self.quorum {
+            return Err(first_error.unwrap_or(ExchangeRateError::NotSupported));
+        }
+
+        Ok(answers)
+    }
+
+    /// Agree a rate for each currency quoted by enough providers
+    fn agree(
+        &self,
+        answers: &[(&str, ExchangeRateResponse)],
+    ) -> (HashMap<CurrencyCode, f64>, Consensus) {
+        let mut quotes: HashMap<CurrencyCode, Vec<(f64, &str)>> = HashMap::new();
+        for (name, rates) in answers {
+            for (code, rate) in &rates.conversion_rates {
+                if rate.is_finite() && *rate > 0.0 {
+                    quotes.entry(*code).or_default().push((*rate, *name));
+                }
+            }
+        }
+
+        let mut conversion_rates = HashMap::new();
+        let mut currencies = HashMap::new();
+        for (code, mut quoted) in quotes {
+            if quoted.len() < self.quorum {
+                continue;
+            }
+
+            quoted.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let sorted: Vec<f64> = quoted.iter().map(|(rate, _)| *rate).collect();
+            let rate = self.method.combine(&sorted);
+            let (low, high) = (sorted[0], sorted[sorted.len() - 1]);
+            let spread = (high - low) / rate;
+
+            conversion_rates.insert(code, rate);
+            currencies.insert(
+                code,
+                CurrencyConsensus {
+                    // In provider order rather than rate order
+                    providers: answers
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .filter(|name| quoted.iter().any(|(_, quoted_by)| quoted_by == name))
+                        .map(str::to_string)
+                        .collect(),
+                    low,
+                    high,
+                    spread,
+                    disputed: spread > self.tolerance,
+                },
+            );
+        }
+
+        let consensus = Consensus {
+            providers: answers.iter().map(|(name, _)| name.to_string()).collect(),
+            currencies,
+        };
+
+        (conversion_rates, consensus)
+    }
+}
+
+#[async_trait]
+impl RateProvider for ConsensusProvider {
+    fn name(&self) -> &str {
+        "consensus"
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        let answers = self.answers(base).await?;
+        let (conversion_rates, consensus) = self.agree(&answers);
+
+        // The quorum is at least one, so there's an answer
+        let oldest = answers
+            .iter()
+            .map(|(_, rates)| rates)
+            .min_by_key(|rates| rates.time_last_update_unix)
+            .ok_or(ExchangeRateError::NotSupported)?;
+        let next_update = answers
+            .iter()
+            .map(|(_, rates)| rates)
+            .min_by_key(|rates| rates.time_next_update_unix)
+            .ok_or(ExchangeRateError::NotSupported)?;
+
+        Ok(ExchangeRateResponse {
+            result: "success".to_string(),
+            documentation: oldest.documentation.clone(),
+            terms_of_use: oldest.terms_of_use.clone(),
+            time_last_update_unix: oldest.time_last_update_unix,
+            time_last_update_utc: oldest.time_last_update_utc.clone(),
+            time_next_update_unix: next_update.time_next_update_unix,
+            time_next_update_utc: next_update.time_next_update_utc.clone(),
+            base_code: base,
+            conversion_rates,
+            provider: Some(self.name().to_string()),
+            consensus: Some(consensus),
+            stale: answers.iter().any(|(_, rates)| rates.stale),
+        })
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        let mut last_error = ExchangeRateError::NotSupported;
+        for provider in &self.providers {
+            match provider.codes().await {
+                Ok(codes) => return Ok(codes),
+                Err(err) => last_error = err,
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FixedProvider, usd};
+    use crate::{ExchangeRateClient, RateTable};
+
+    #[test]
+    fn test_methods() {
+        assert_eq!(ConsensusMethod::Median.combine(&[1.0, 2.0, 9.0]), 2.0);
+        assert_eq!(ConsensusMethod::Median.combine(&[1.0, 2.0, 3.0, 9.0]), 2.5);
+        assert_eq!(
+            ConsensusMethod::TrimmedMean(0.25).combine(&[1.0, 2.0, 3.0, 9.0]),
+            2.5
+        );
+        assert_eq!(
+            ConsensusMethod::TrimmedMean(0.0).combine(&[1.0, 2.0, 3.0, 9.0]),
+            3.75
+        );
+        // Trimming everything falls back to the median
+        assert_eq!(
+            ConsensusMethod::TrimmedMean(0.5).combine(&[1.0, 2.0, 9.0, 9.0]),
+            5.5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_median_resists_a_faulty_provider() {
+        let consensus = ConsensusProvider::new(vec![
+            FixedProvider::quoting("first", &[("EUR", 0.90), ("GBP", 0.750)]),
+            FixedProvider::quoting("second", &[("EUR", 0.91), ("GBP", 0.751), ("JPY", 150.0)]),
+            FixedProvider::quoting("faulty", &[("EUR", 9.1), ("GBP", 0.752)]),
+        ])
+        .tolerance(0.01);
+
+        let rates = consensus.latest(usd()).await.unwrap();
+        assert_eq!(rates.provider.as_deref(), Some("consensus"));
+        assert_eq!(rates.get_rate("EUR"), Some(0.91));
+        assert_eq!(rates.get_rate("GBP"), Some(0.751));
+        assert_eq!(rates.get_rate("JPY"), Some(150.0));
+
+        let agreed = rates.consensus.as_ref().unwrap();
+        assert_eq!(agreed.providers, ["first", "second", "faulty"]);
+        assert_eq!(agreed.disputed(), ["EUR".parse::<CurrencyCode>().unwrap()]);
+
+        let eur = &agreed.currencies[&"EUR".parse::<CurrencyCode>().unwrap()];
+        assert_eq!((eur.low, eur.high), (0.90, 9.1));
+        assert!((eur.spread - (9.1 - 0.9) / 0.91).abs() < 1e-12);
+
+        let jpy = &agreed.currencies[&"JPY".parse::<CurrencyCode>().unwrap()];
+        assert_eq!(jpy.providers, ["second"]);
+        assert_eq!(jpy.spread, 0.0);
+        assert!(!jpy.disputed);
+
+        // Rates agreed by consensus work anywhere a response does
+        let table = RateTable::from(rates);
+        assert!((table.rate("EUR", "GBP").unwrap() - 0.751 / 0.91).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_and_trimmed_mean() {
+        let providers: Vec<Arc<dyn RateProvider>> = vec![
+            FixedProvider::quoting("first", &[("EUR", 0.90), ("JPY", 150.0)]),
+            FixedProvider::failing("down"),
+            FixedProvider::quoting("second", &[("EUR", 0.92)]),
+        ];
+
+        assert!(matches!(
+            ConsensusProvider::new(providers.clone())
+                .quorum(3)
+                .latest(usd())
+                .await,
+            Err(ExchangeRateError::HttpError(_))
+        ));
+
+        let rates = ConsensusProvider::new(providers)
+            .method(ConsensusMethod::TrimmedMean(0.1))
+            .quorum(2)
+            .latest(usd())
+            .await
+            .unwrap();
+        assert!((rates.get_rate("EUR").unwrap() - 0.91).abs() < 1e-12);
+        // Only one provider quoted JPY
+        assert_eq!(rates.get_rate("JPY"), None);
+        assert_eq!(
+            rates.consensus.unwrap().providers,
+            ["first".to_string(), "second".to_string()]
+        );
+
+        assert!(matches!(
+            ConsensusProvider::new(Vec::new()).latest(usd()).await,
+            Err(ExchangeRateError::NotSupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_client_caches_consensus() {
+        let consensus = ConsensusProvider::new(vec![
+            FixedProvider::quoting("first", &[("EUR", 0.90)]),
+            FixedProvider::quoting("second", &[("EUR", 0.92)]),
+        ]);
+        let client = ExchangeRateClient::builder()
+            .provider(Arc::new(consensus))
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            let rates = client.get_latest_rates("USD").await.unwrap();
+            assert!((rates.get_rate("EUR").unwrap() - 0.91).abs() < 1e-12);
+            assert_eq!(rates.consensus.unwrap().providers.len(), 2);
+        }
+
+        let converted = client.convert(100.0, "USD", "EUR").await.unwrap();
+        assert!((converted - 91.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,361 @@
+//! ISO 4217 currency registry
+//!
+//! Static data for every active ISO 4217 currency, plus the handful of non-ISO
+//! codes the Exchange Rate API quotes, so amounts can be validated, rounded and
+//! displayed without calling the API.
+
+use crate::currency_code::CurrencyCode;
+
+/// A currency from the built-in registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    code: CurrencyCode,
+    numeric: Option<u16>,
+    name: &'static str,
+    minor_units: Option<u8>,
+    symbol: &'static str,
+    narrow_symbol: &'static str,
+}
+
+impl Currency {
+    /// The alphabetic currency code, such as `USD`
+    #[must_use]
+    pub const fn code(&self) -> CurrencyCode {
+        self.code
+    }
+
+    /// The ISO 4217 numeric code, such as `840` for `USD`
+    ///
+    /// `None` for codes the API supports that have no ISO 4217 numeric code.
+    #[must_use]
+    pub const fn numeric(&self) -> Option<u16> {
+        self.numeric
+    }
+
+    /// The English name of the currency
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The number of digits after the decimal separator (the minor-unit exponent)
+    ///
+    /// `None` for units without a minor unit, such as precious metals and `XDR`.
+    #[must_use]
+    pub const fn minor_units(&self) -> Option<u8> {
+        self.minor_units
+    }
+
+    /// The unambiguous symbol, such as `US$` or `CA$`
+    ///
+    /// Falls back to the currency code when there is no widely used symbol.
+    #[must_use]
+    pub const fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    /// The local symbol, such as `$` for both `USD` and `CAD`
+    ///
+    /// Only unambiguous alongside the currency code or in a known locale.
+    #[must_use]
+    pub const fn narrow_symbol(&self) -> &'static str {
+        self.narrow_symbol
+    }
+}
+
+/// Find a currency by its alphabetic code, ignoring case
+///
+/// # Examples
+///
+/// ```
+/// use client::currency;
+///
+/// let yen = currency::find("jpy").unwrap();
+/// assert_eq!(yen.numeric(), Some(392));
+/// assert_eq!(yen.minor_units(), Some(0));
+/// assert_eq!(yen.symbol(), "¥");
+/// ```
+#[must_use]
+pub fn find(code: &str) -> Option<&'static Currency> {
+    let code = CurrencyCode::parse_well_formed(code).ok()?;
+
+    ISO_4217
+        .binary_search_by(|currency| currency.code.cmp(&code))
+        .ok()
+        .map(|index| &ISO_4217[index])
+        .or_else(|| SUPPLEMENTARY.iter().find(|currency| currency.code == code))
+}
+
+/// Find an active ISO 4217 currency by its numeric code
+#[must_use]
+pub fn find_by_numeric(numeric: u16) -> Option<&'static Currency> {
+    ISO_4217
+        .iter()
+        .find(|currency| currency.numeric == Some(numeric))
+}
+
+/// Iterate over every currency in the registry
+pub fn all() -> impl Iterator<Item = &'static Currency> {
+    ISO_4217.iter().chain(SUPPLEMENTARY)
+}
+
+const fn currency(
+    code: &[u8; 3],
+    numeric: Option<u16>,
+    name: &'static str,
+    minor_units: Option<u8>,
+    symbol: &'static str,
+    narrow_symbol: &'static str,
+) -> Currency {
+    Currency {
+        code: CurrencyCode::from_ascii(*code),
+        numeric,
+        name,
+        minor_units,
+        symbol,
+        narrow_symbol,
+    }
+}
+
+/// Active ISO 4217 currencies, sorted by code
+#[rustfmt::skip]
+const ISO_4217: &[Currency] = &[
+    currency(b"AED", Some(784), "UAE Dirham", Some(2), "AED", "د.إ"),
+    currency(b"AFN", Some(971), "Afghan Afghani", Some(2), "AFN", "؋"),
+    currency(b"ALL", Some(8), "Albanian Lek", Some(2), "ALL", "L"),
+    currency(b"AMD", Some(51), "Armenian Dram", Some(2), "AMD", "֏"),
+    currency(b"AOA", Some(973), "Angolan Kwanza", Some(2), "AOA", "Kz"),
+    currency(b"ARS", Some(32), "Argentine Peso", Some(2), "ARS", "$"),
+    currency(b"AUD", Some(36), "Australian Dollar", Some(2), "A$", "$"),
+    currency(b"AWG", Some(533), "Aruban Florin", Some(2), "AWG", "ƒ"),
+    currency(b"AZN", Some(944), "Azerbaijani Manat", Some(2), "AZN", "₼"),
+    currency(b"BAM", Some(977), "Bosnia-Herzegovina Convertible Mark", Some(2), "BAM", "KM"),
+    currency(b"BBD", Some(52), "Barbadian Dollar", Some(2), "BBD", "$"),
+    currency(b"BDT", Some(50), "Bangladeshi Taka", Some(2), "BDT", "৳"),
+    currency(b"BGN", Some(975), "Bulgarian Lev", Some(2), "BGN", "лв"),
+    currency(b"BHD", Some(48), "Bahraini Dinar", Some(3), "BHD", "BD"),
+    currency(b"BIF", Some(108), "Burundian Franc", Some(0), "BIF", "FBu"),
+    currency(b"BMD", Some(60), "Bermudan Dollar", Some(2), "BMD", "$"),
+    currency(b"BND", Some(96), "Brunei Dollar", Some(2), "BND", "$"),
+    currency(b"BOB", Some(68), "Bolivian Boliviano", Some(2), "BOB", "Bs"),
+    currency(b"BOV", Some(984), "Bolivian Mvdol", Some(2), "BOV", "BOV"),
+    currency(b"BRL", Some(986), "Brazilian Real", Some(2), "R$", "R$"),
+    currency(b"BSD", Some(44), "Bahamian Dollar", Some(2), "BSD", "$"),
+    currency(b"BTN", Some(64), "Bhutanese Ngultrum", Some(2), "BTN", "Nu."),
+    currency(b"BWP", Some(72), "Botswanan Pula", Some(2), "BWP", "P"),
+    currency(b"BYN", Some(933), "Belarusian Ruble", Some(2), "BYN", "Br"),
+    currency(b"BZD", Some(84), "Belize Dollar", Some(2), "BZD", "$"),
+    currency(b"CAD", Some(124), "Canadian Dollar", Some(2), "CA$", "$"),
+    currency(b"CDF", Some(976), "Congolese Franc", Some(2), "CDF", "FC"),
+    currency(b"CHE", Some(947), "WIR Euro", Some(2), "CHE", "CHE"),
+    currency(b"CHF", Some(756), "Swiss Franc", Some(2), "CHF", "Fr."),
+    currency(b"CHW", Some(948), "WIR Franc", Some(2), "CHW", "CHW"),
+    currency(b"CLF", Some(990), "Chilean Unit of Account (UF)", Some(4), "CLF", "UF"),
+    currency(b"CLP", Some(152), "Chilean Peso", Some(0), "CLP", "$"),
+    currency(b"CNY", Some(156), "Chinese Yuan", Some(2), "CN¥", "¥"),
+    currency(b"COP", Some(170), "Colombian Peso", Some(2), "COP", "$"),
+    currency(b"COU", Some(970), "Colombian Real Value Unit", Some(2), "COU", "COU"),
+    currency(b"CRC", Some(188), "Costa Rican Colón", Some(2), "CRC", "₡"),
+    currency(b"CUC", Some(931), "Cuban Convertible Peso", Some(2), "CUC", "$"),
+    currency(b"CUP", Some(192), "Cuban Peso", Some(2), "CUP", "$"),
+    currency(b"CVE", Some(132), "Cape Verdean Escudo", Some(2), "CVE", "Esc"),
+    currency(b"CZK", Some(203), "Czech Koruna", Some(2), "CZK", "Kč"),
+    currency(b"DJF", Some(262), "Djiboutian Franc", Some(0), "DJF", "Fdj"),
+    currency(b"DKK", Some(208), "Danish Krone", Some(2), "DKK", "kr"),
+    currency(b"DOP", Some(214), "Dominican Peso", Some(2), "DOP", "$"),
+    currency(b"DZD", Some(12), "Algerian Dinar", Some(2), "DZD", "DA"),
+    currency(b"EGP", Some(818), "Egyptian Pound", Some(2), "EGP", "E£"),
+    currency(b"ERN", Some(232), "Eritrean Nakfa", Some(2), "ERN", "Nfk"),
+    currency(b"ETB", Some(230), "Ethiopian Birr", Some(2), "ETB", "Br"),
+    currency(b"EUR", Some(978), "Euro", Some(2), "€", "€"),
+    currency(b"FJD", Some(242), "Fijian Dollar", Some(2), "FJD", "$"),
+    currency(b"FKP", Some(238), "Falkland Islands Pound", Some(2), "FKP", "£"),
+    currency(b"GBP", Some(826), "British Pound", Some(2), "£", "£"),
+    currency(b"GEL", Some(981), "Georgian Lari", Some(2), "GEL", "₾"),
+    currency(b"GHS", Some(936), "Ghanaian Cedi", Some(2), "GHS", "GH₵"),
+    currency(b"GIP", Some(292), "Gibraltar Pound", Some(2), "GIP", "£"),
+    currency(b"GMD", Some(270), "Gambian Dalasi", Some(2), "GMD", "D"),
+    currency(b"GNF", Some(324), "Guinean Franc", Some(0), "GNF", "FG"),
+    currency(b"GTQ", Some(320), "Guatemalan Quetzal", Some(2), "GTQ", "Q"),
+    currency(b"GYD", Some(328), "Guyanaese Dollar", Some(2), "GYD", "$"),
+    currency(b"HKD", Some(344), "Hong Kong Dollar", Some(2), "HK$", "$"),
+    currency(b"HNL", Some(340), "Honduran Lempira", Some(2), "HNL", "L"),
+    currency(b"HTG", Some(332), "Haitian Gourde", Some(2), "HTG", "G"),
+    currency(b"HUF", Some(348), "Hungarian Forint", Some(2), "HUF", "Ft"),
+    currency(b"IDR", Some(360), "Indonesian Rupiah", Some(2), "IDR", "Rp"),
+    currency(b"ILS", Some(376), "Israeli New Shekel", Some(2), "₪", "₪"),
+    currency(b"INR", Some(356), "Indian Rupee", Some(2), "₹", "₹"),
+    currency(b"IQD", Some(368), "Iraqi Dinar", Some(3), "IQD", "ع.د"),
+    currency(b"IRR", Some(364), "Iranian Rial", Some(2), "IRR", "﷼"),
+    currency(b"ISK", Some(352), "Icelandic Króna", Some(0), "ISK", "kr"),
+    currency(b"JMD", Some(388), "Jamaican Dollar", Some(2), "JMD", "$"),
+    currency(b"JOD", Some(400), "Jordanian Dinar", Some(3), "JOD", "JD"),
+    currency(b"JPY", Some(392), "Japanese Yen", Some(0), "¥", "¥"),
+    currency(b"KES", Some(404), "Kenyan Shilling", Some(2), "KES", "KSh"),
+    currency(b"KGS", Some(417), "Kyrgystani Som", Some(2), "KGS", "som"),
+    currency(b"KHR", Some(116), "Cambodian Riel", Some(2), "KHR", "៛"),
+    currency(b"KMF", Some(174), "Comorian Franc", Some(0), "KMF", "CF"),
+    currency(b"KPW", Some(408), "North Korean Won", Some(2), "KPW", "₩"),
+    currency(b"KRW", Some(410), "South Korean Won", Some(0), "₩", "₩"),
+    currency(b"KWD", Some(414), "Kuwaiti Dinar", Some(3), "KWD", "KD"),
+    currency(b"KYD", Some(136), "Cayman Islands Dollar", Some(2), "KYD", "$"),
+    currency(b"KZT", Some(398), "Kazakhstani Tenge", Some(2), "KZT", "₸"),
+    currency(b"LAK", Some(418), "Laotian Kip", Some(2), "LAK", "₭"),
+    currency(b"LBP", Some(422), "Lebanese Pound", Some(2), "LBP", "L£"),
+    currency(b"LKR", Some(144), "Sri Lankan Rupee", Some(2), "LKR", "Rs"),
+    currency(b"LRD", Some(430), "Liberian Dollar", Some(2), "LRD", "$"),
+    currency(b"LSL", Some(426), "Lesotho Loti", Some(2), "LSL", "L"),
+    currency(b"LYD", Some(434), "Libyan Dinar", Some(3), "LYD", "LD"),
+    currency(b"MAD", Some(504), "Moroccan Dirham", Some(2), "MAD", "DH"),
+    currency(b"MDL", Some(498), "Moldovan Leu", Some(2), "MDL", "L"),
+    currency(b"MGA", Some(969), "Malagasy Ariary", Some(2), "MGA", "Ar"),
+    currency(b"MKD", Some(807), "Macedonian Denar", Some(2), "MKD", "ден"),
+    currency(b"MMK", Some(104), "Myanmar Kyat", Some(2), "MMK", "K"),
+    currency(b"MNT", Some(496), "Mongolian Tugrik", Some(2), "MNT", "₮"),
+    currency(b"MOP", Some(446), "Macanese Pataca", Some(2), "MOP", "MOP$"),
+    currency(b"MRU", Some(929), "Mauritanian Ouguiya", Some(2), "MRU", "UM"),
+    currency(b"MUR", Some(480), "Mauritian Rupee", Some(2), "MUR", "Rs"),
+    currency(b"MVR", Some(462), "Maldivian Rufiyaa", Some(2), "MVR", "Rf"),
+    currency(b"MWK", Some(454), "Malawian Kwacha", Some(2), "MWK", "MK"),
+    currency(b"MXN", Some(484), "Mexican Peso", Some(2), "MX$", "$"),
+    currency(b"MXV", Some(979), "Mexican Investment Unit", Some(2), "MXV", "MXV"),
+    currency(b"MYR", Some(458), "Malaysian Ringgit", Some(2), "MYR", "RM"),
+    currency(b"MZN", Some(943), "Mozambican Metical", Some(2), "MZN", "MT"),
+    currency(b"NAD", Some(516), "Namibian Dollar", Some(2), "NAD", "$"),
+    currency(b"NGN", Some(566), "Nigerian Naira", Some(2), "NGN", "₦"),
+    currency(b"NIO", Some(558), "Nicaraguan Córdoba", Some(2), "NIO", "C$"),
+    currency(b"NOK", Some(578), "Norwegian Krone", Some(2), "NOK", "kr"),
+    currency(b"NPR", Some(524), "Nepalese Rupee", Some(2), "NPR", "Rs"),
+    currency(b"NZD", Some(554), "New Zealand Dollar", Some(2), "NZ$", "$"),
+    currency(b"OMR", Some(512), "Omani Rial", Some(3), "OMR", "ر.ع."),
+    currency(b"PAB", Some(590), "Panamanian Balboa", Some(2), "PAB", "B/."),
+    currency(b"PEN", Some(604), "Peruvian Sol", Some(2), "PEN", "S/"),
+    currency(b"PGK", Some(598), "Papua New Guinean Kina", Some(2), "PGK", "K"),
+    currency(b"PHP", Some(608), "Philippine Peso", Some(2), "₱", "₱"),
+    currency(b"PKR", Some(586), "Pakistani Rupee", Some(2), "PKR", "Rs"),
+    currency(b"PLN", Some(985), "Polish Zloty", Some(2), "PLN", "zł"),
+    currency(b"PYG", Some(600), "Paraguayan Guarani", Some(0), "PYG", "₲"),
+    currency(b"QAR", Some(634), "Qatari Riyal", Some(2), "QAR", "QR"),
+    currency(b"RON", Some(946), "Romanian Leu", Some(2), "RON", "lei"),
+    currency(b"RSD", Some(941), "Serbian Dinar", Some(2), "RSD", "din"),
+    currency(b"RUB", Some(643), "Russian Ruble", Some(2), "RUB", "₽"),
+    currency(b"RWF", Some(646), "Rwandan Franc", Some(0), "RWF", "RF"),
+    currency(b"SAR", Some(682), "Saudi Riyal", Some(2), "SAR", "SR"),
+    currency(b"SBD", Some(90), "Solomon Islands Dollar", Some(2), "SBD", "$"),
+    currency(b"SCR", Some(690), "Seychellois Rupee", Some(2), "SCR", "SR"),
+    currency(b"SDG", Some(938), "Sudanese Pound", Some(2), "SDG", "SDG"),
+    currency(b"SEK", Some(752), "Swedish Krona", Some(2), "SEK", "kr"),
+    currency(b"SGD", Some(702), "Singapore Dollar", Some(2), "SGD", "$"),
+    currency(b"SHP", Some(654), "St. Helena Pound", Some(2), "SHP", "£"),
+    currency(b"SLE", Some(925), "Sierra Leonean Leone", Some(2), "SLE", "Le"),
+    currency(b"SLL", Some(694), "Sierra Leonean Leone (1964-2022)", Some(2), "SLL", "Le"),
+    currency(b"SOS", Some(706), "Somali Shilling", Some(2), "SOS", "Sh"),
+    currency(b"SRD", Some(968), "Surinamese Dollar", Some(2), "SRD", "$"),
+    currency(b"SSP", Some(728), "South Sudanese Pound", Some(2), "SSP", "£"),
+    currency(b"STN", Some(930), "São Tomé & Príncipe Dobra", Some(2), "STN", "Db"),
+    currency(b"SVC", Some(222), "Salvadoran Colón", Some(2), "SVC", "₡"),
+    currency(b"SYP", Some(760), "Syrian Pound", Some(2), "SYP", "£"),
+    currency(b"SZL", Some(748), "Swazi Lilangeni", Some(2), "SZL", "E"),
+    currency(b"THB", Some(764), "Thai Baht", Some(2), "THB", "฿"),
+    currency(b"TJS", Some(972), "Tajikistani Somoni", Some(2), "TJS", "SM"),
+    currency(b"TMT", Some(934), "Turkmenistani Manat", Some(2), "TMT", "m"),
+    currency(b"TND", Some(788), "Tunisian Dinar", Some(3), "TND", "DT"),
+    currency(b"TOP", Some(776), "Tongan Paʻanga", Some(2), "TOP", "T$"),
+    currency(b"TRY", Some(949), "Turkish Lira", Some(2), "TRY", "₺"),
+    currency(b"TTD", Some(780), "Trinidad & Tobago Dollar", Some(2), "TTD", "$"),
+    currency(b"TWD", Some(901), "New Taiwan Dollar", Some(2), "NT$", "$"),
+    currency(b"TZS", Some(834), "Tanzanian Shilling", Some(2), "TZS", "TSh"),
+    currency(b"UAH", Some(980), "Ukrainian Hryvnia", Some(2), "UAH", "₴"),
+    currency(b"UGX", Some(800), "Ugandan Shilling", Some(0), "UGX", "USh"),
+    currency(b"USD", Some(840), "US Dollar", Some(2), "$", "$"),
+    currency(b"USN", Some(997), "US Dollar (Next day)", Some(2), "USN", "USN"),
+    currency(b"UYI", Some(940), "Uruguayan Peso (Indexed Units)", Some(0), "UYI", "UYI"),
+    currency(b"UYU", Some(858), "Uruguayan Peso", Some(2), "UYU", "$"),
+    currency(b"UYW", Some(927), "Uruguayan Nominal Wage Index Unit", Some(4), "UYW", "UYW"),
+    currency(b"UZS", Some(860), "Uzbekistani Som", Some(2), "UZS", "soʻm"),
+    currency(b"VED", Some(926), "Venezuelan Digital Bolívar", Some(2), "VED", "Bs.D"),
+    currency(b"VES", Some(928), "Venezuelan Bolívar", Some(2), "VES", "Bs.S"),
+    currency(b"VND", Some(704), "Vietnamese Dong", Some(0), "₫", "₫"),
+    currency(b"VUV", Some(548), "Vanuatu Vatu", Some(0), "VUV", "VT"),
+    currency(b"WST", Some(882), "Samoan Tala", Some(2), "WST", "WS$"),
+    currency(b"XAF", Some(950), "Central African CFA Franc", Some(0), "FCFA", "FCFA"),
+    currency(b"XAG", Some(961), "Silver", None, "XAG", "XAG"),
+    currency(b"XAU", Some(959), "Gold", None, "XAU", "XAU"),
+    currency(b"XBA", Some(955), "European Composite Unit", None, "XBA", "XBA"),
+    currency(b"XBB", Some(956), "European Monetary Unit", None, "XBB", "XBB"),
+    currency(b"XBC", Some(957), "European Unit of Account (XBC)", None, "XBC", "XBC"),
+    currency(b"XBD", Some(958), "European Unit of Account (XBD)", None, "XBD", "XBD"),
+    currency(b"XCD", Some(951), "East Caribbean Dollar", Some(2), "EC$", "$"),
+    currency(b"XCG", Some(532), "Caribbean Guilder", Some(2), "XCG", "Cg"),
+    currency(b"XDR", Some(960), "Special Drawing Rights", None, "XDR", "XDR"),
+    currency(b"XOF", Some(952), "West African CFA Franc", Some(0), "F CFA", "F CFA"),
+    currency(b"XPD", Some(964), "Palladium", None, "XPD", "XPD"),
+    currency(b"XPF", Some(953), "CFP Franc", Some(0), "CFPF", "CFPF"),
+    currency(b"XPT", Some(962), "Platinum", None, "XPT", "XPT"),
+    currency(b"XSU", Some(994), "Sucre", None, "XSU", "XSU"),
+    currency(b"XTS", Some(963), "Testing Currency Code", None, "XTS", "XTS"),
+    currency(b"XUA", Some(965), "ADB Unit of Account", None, "XUA", "XUA"),
+    currency(b"XXX", Some(999), "Unknown Currency", None, "XXX", "XXX"),
+    currency(b"YER", Some(886), "Yemeni Rial", Some(2), "YER", "﷼"),
+    currency(b"ZAR", Some(710), "South African Rand", Some(2), "ZAR", "R"),
+    currency(b"ZMW", Some(967), "Zambian Kwacha", Some(2), "ZMW", "ZK"),
+    currency(b"ZWG", Some(924), "Zimbabwean Gold", Some(2), "ZWG", "ZiG"),
+    currency(b"ZWL", Some(932), "Zimbabwean Dollar (2009)", Some(2), "ZWL", "$"),
+];
+
+/// Codes outside the active ISO 4217 list that the Exchange Rate API supports
+///
+/// These are local currencies pegged to another currency (e.g. the Jersey pound)
+/// and codes that were withdrawn from ISO 4217 but are still quoted.
+#[rustfmt::skip]
+const SUPPLEMENTARY: &[Currency] = &[
+    currency(b"ANG", Some(532), "Netherlands Antillean Guilder", Some(2), "ANG", "ƒ"),
+    currency(b"FOK", None, "Faroese Króna", Some(2), "FOK", "kr"),
+    currency(b"GGP", None, "Guernsey Pound", Some(2), "GGP", "£"),
+    currency(b"HRK", Some(191), "Croatian Kuna", Some(2), "HRK", "kn"),
+    currency(b"IMP", None, "Manx Pound", Some(2), "IMP", "£"),
+    currency(b"JEP", None, "Jersey Pound", Some(2), "JEP", "£"),
+    currency(b"KID", None, "Kiribati Dollar", Some(2), "KID", "$"),
+    currency(b"TVD", None, "Tuvaluan Dollar", Some(2), "TVD", "$"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_and_unique() {
+        assert!(ISO_4217.windows(2).all(|pair| pair[0].code < pair[1].code));
+        assert!(
+            SUPPLEMENTARY
+                .iter()
+                .all(|currency| ISO_4217.iter().all(|iso| iso.code != currency.code))
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let dinar = find("kwd").unwrap();
+        assert_eq!(dinar.code(), "KWD");
+        assert_eq!(dinar.numeric(), Some(414));
+        assert_eq!(dinar.minor_units(), Some(3));
+
+        let usd = find("USD").unwrap();
+        assert_eq!(usd.name(), "US Dollar");
+        assert_eq!(usd.narrow_symbol(), "$");
+        assert_eq!(find("CAD").unwrap().symbol(), "CA$");
+
+        assert_eq!(find("XAU").unwrap().minor_units(), None);
+        assert_eq!(find("GGP").unwrap().numeric(), None);
+        assert!(find("XYZ").is_none());
+        assert!(find("EURO").is_none());
+    }
+
+    #[test]
+    fn test_find_by_numeric() {
+        assert_eq!(find_by_numeric(978).unwrap().code(), "EUR");
+        // The withdrawn guilder shares its numeric code with its replacement
+        assert_eq!(find_by_numeric(532).unwrap().code(), "XCG");
+        assert!(find_by_numeric(1).is_none());
+    }
+}
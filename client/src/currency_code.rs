@@ -0,0 +1,275 @@
+use crate::currency::{self, Currency};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Errors that can occur when parsing a currency code
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CurrencyCodeError {
+    /// The code is not exactly three characters long
+    #[error("Invalid currency code '{0}': should be 3 letters")]
+    InvalidLength(String),
+
+    /// The code contains characters other than ASCII letters
+    #[error("Invalid currency code '{0}': should only contain letters")]
+    InvalidCharacters(String),
+
+    /// The code is well-formed but not a known ISO 4217 code
+    #[error("Unknown currency code '{0}': not an ISO 4217 currency code")]
+    Unknown(String),
+}
+
+/// An ISO 4217 currency code, such as `USD`
+///
+/// Codes are normalised to uppercase and validated against ISO 4217 (plus the few
+/// non-ISO codes the Exchange Rate API supports) when parsed, so invalid codes are
+/// rejected before any request is made.
+///
+/// Codes deserialized from API responses are only checked for being three letters,
+/// so that a currency newly added by the provider doesn't break parsing.
+///
+/// # Examples
+///
+/// ```
+/// use client::CurrencyCode;
+///
+/// let code: CurrencyCode = "usd".parse().unwrap();
+/// assert_eq!(code, "USD");
+///
+/// assert!("EURO".parse::<CurrencyCode>().is_err());
+/// assert!("XYZ".parse::<CurrencyCode>().is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CurrencyCode([u8; 3]);
+
+impl CurrencyCode {
+    /// Parse and validate a currency code, normalising it to uppercase
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code is not three letters or is not a known
+    /// ISO 4217 code
+    pub fn new(code: &str) -> Result<Self, CurrencyCodeError> {
+        let currency_code = Self::parse_well_formed(code)?;
+
+        if !currency_code.is_known() {
+            return Err(CurrencyCodeError::Unknown(currency_code.to_string()));
+        }
+
+        Ok(currency_code)
+    }
+
+    /// Create a code from bytes already known to be uppercase ASCII letters
+    pub(crate) const fn from_ascii(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a code, only checking that it is three letters
+    pub(crate) fn parse_well_formed(code: &str) -> Result<Self, CurrencyCodeError> {
+        let trimmed = code.trim();
+
+        let bytes: [u8; 3] = trimmed
+            .as_bytes()
+            .try_into()
+            .map_err(|_| CurrencyCodeError::InvalidLength(code.to_string()))?;
+
+        if !bytes.iter().all(u8::is_ascii_alphabetic) {
+            return Err(CurrencyCodeError::InvalidCharacters(code.to_string()));
+        }
+
+        Ok(Self(bytes.map(|b| b.to_ascii_uppercase())))
+    }
+
+    /// Check if this is an ISO 4217 code or a supplementary code supported by the API
+    #[must_use]
+    pub fn is_known(&self) -> bool {
+        self.currency().is_some()
+    }
+
+    /// Look up this code in the built-in [`currency`] registry
+    #[must_use]
+    pub fn currency(&self) -> Option<&'static Currency> {
+        currency::find(self.as_str())
+    }
+
+    /// The code as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Only ASCII letters are ever stored
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl fmt::Debug for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CurrencyCode({})", self.as_str())
+    }
+}
+
+// Hash as a `str` so that maps keyed by `CurrencyCode` can be queried with `&str`
+impl Hash for CurrencyCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Borrow<str> for CurrencyCode {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for CurrencyCode {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for CurrencyCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for CurrencyCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = CurrencyCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for CurrencyCode {
+    type Error = CurrencyCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for CurrencyCode {
+    type Error = CurrencyCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+impl Serialize for CurrencyCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Self::parse_well_formed(&code).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Types that can be converted into a validated [`CurrencyCode`]
+///
+/// Client methods accept any of these, so both `"USD"` and an already parsed
+/// `CurrencyCode` can be passed.
+pub trait IntoCurrencyCode {
+    /// Convert into a validated currency code
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a valid currency code
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError>;
+}
+
+impl IntoCurrencyCode for CurrencyCode {
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError> {
+        Ok(self)
+    }
+}
+
+impl IntoCurrencyCode for &CurrencyCode {
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError> {
+        Ok(*self)
+    }
+}
+
+impl IntoCurrencyCode for &str {
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError> {
+        CurrencyCode::new(self)
+    }
+}
+
+impl IntoCurrencyCode for String {
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError> {
+        CurrencyCode::new(&self)
+    }
+}
+
+impl IntoCurrencyCode for &String {
+    fn into_currency_code(self) -> Result<CurrencyCode, CurrencyCodeError> {
+        CurrencyCode::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_normalises_case() {
+        assert_eq!(CurrencyCode::new("usd").unwrap(), "USD");
+        assert_eq!(CurrencyCode::new(" Eur ").unwrap(), "EUR");
+        assert_eq!(CurrencyCode::new("GGP").unwrap().as_str(), "GGP");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_codes() {
+        assert_eq!(
+            CurrencyCode::new("US"),
+            Err(CurrencyCodeError::InvalidLength("US".to_string()))
+        );
+        assert_eq!(
+            CurrencyCode::new("EURO"),
+            Err(CurrencyCodeError::InvalidLength("EURO".to_string()))
+        );
+        assert_eq!(
+            CurrencyCode::new("U$D"),
+            Err(CurrencyCodeError::InvalidCharacters("U$D".to_string()))
+        );
+        assert_eq!(
+            CurrencyCode::new("xyz"),
+            Err(CurrencyCodeError::Unknown("XYZ".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip_and_map_lookup() {
+        let rates: HashMap<CurrencyCode, f64> =
+            serde_json::from_str(r#"{"EUR":0.9,"ABC":1.5}"#).unwrap();
+
+        // Well-formed codes from the API are accepted even if unknown
+        assert_eq!(rates.get("ABC"), Some(&1.5));
+        assert_eq!(rates.get(&CurrencyCode::new("eur").unwrap()), Some(&0.9));
+
+        let json = serde_json::to_string(&CurrencyCode::new("gbp").unwrap()).unwrap();
+        assert_eq!(json, r#""GBP""#);
+
+        assert!(serde_json::from_str::<CurrencyCode>(r#""EURO""#).is_err());
+    }
+}
@@ -0,0 +1,651 @@
+//! European Central Bank euro foreign exchange reference rates
+
+use crate::{
+    CurrencyCode, ExchangeRateError, ExchangeRateResponse, HistoricalRatesResponse, HttpRequest,
+    HttpTransport, IntoCurrencyCode, RateProvider, ReqwestTransport,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const DEFAULT_BASE_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref";
+const DAILY_FILE: &str = "eurofxref-daily.xml";
+const RECENT_HISTORY_FILE: &str = "eurofxref-hist-90d.xml";
+const FULL_HISTORY_FILE: &str = "eurofxref-hist.xml";
+
+const DOCUMENTATION_URL: &str = "https://www.ecb.europa.eu/stats/policy_and_exchange_rates/euro_reference_exchange_rates/html/index.en.html";
+const TERMS_OF_USE_URL: &str = "https://www.ecb.europa.eu/services/disclaimer/html/index.en.html";
+
+/// The recent history file covers about this many days, so older dates need the full file
+const RECENT_HISTORY_DAYS: i64 = 85;
+
+/// Rates are published around 16:00 CET, which is 15:00 UTC in winter and an
+/// hour after publication in summer
+const PUBLISH_HOUR_UTC: u32 = 15;
+
+/// The first day of reference rates, when the euro was introduced
+const FIRST_FIX: NaiveDate = NaiveDate::from_ymd_opt(1999, 1, 4).unwrap();
+
+/// The reference rates for one day, in units of each currency per euro
+///
+/// Parsed from the ECB's XML files (`eurofxref-daily.xml`, `eurofxref-hist.xml`
+/// and `eurofxref-hist-90d.xml`) or the CSV files in its zip downloads
+/// (`eurofxref.csv` and `eurofxref-hist.csv`). Currencies missing from the
+/// [`currency`](crate::currency) registry, such as those replaced by the euro,
+/// are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use client::EcbReferenceRates;
+///
+/// let csv = "Date, USD, GBP, \n14 May 2025, 1.1185, 0.84130, \n";
+/// let days = EcbReferenceRates::parse_csv(csv).unwrap();
+///
+/// let rates = days[0].to_response("GBP").unwrap();
+/// assert_eq!(rates.base_code, "GBP");
+/// assert!((rates.get_rate("USD").unwrap() - 1.1185 / 0.8413).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EcbReferenceRates {
+    /// The day the rates were set
+    pub date: NaiveDate,
+
+    /// Units of each currency per euro, not including the euro itself
+    pub rates: HashMap<CurrencyCode, f64>,
+}
+
+impl EcbReferenceRates {
+    /// Parse an ECB XML file into the rates for each day it contains, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidResponse` if the XML is malformed or contains no rates
+    pub fn parse_xml(xml: &str) -> Result<Vec<Self>, ExchangeRateError> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|e| ExchangeRateError::InvalidResponse(e.to_string()))?;
+
+        let mut days = document
+            .descendants()
+            .filter(|node| node.has_tag_name("Cube"))
+            .filter_map(|node| Some((node, node.attribute("time")?)))
+            .map(|(node, time)| {
+                let mut rates = HashMap::new();
+                for cube in node.children().filter(|child| child.has_tag_name("Cube")) {
+                    let (Some(currency), Some(rate)) =
+                        (cube.attribute("currency"), cube.attribute("rate"))
+                    else {
+                        continue;
+                    };
+                    if let Some(code) = parse_code(currency)? {
+                        rates.insert(code, parse_rate(rate)?);
+                    }
+                }
+
+                Ok(Self {
+                    date: parse_date(time)?,
+                    rates,
+                })
+            })
+            .collect::<Result<Vec<_>, ExchangeRateError>>()?;
+
+        sort_newest_first(&mut days)?;
+        Ok(days)
+    }
+
+    /// Parse an ECB CSV file into the rates for each day it contains, newest first
+    ///
+    /// Dates may be ISO 8601 (`2025-05-14`) as in the history file, or written out
+    /// (`14 May 2025`) as in the daily file. `N/A` and empty values are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidResponse` if a row can't be parsed or there are no rates
+    pub fn parse_csv(csv: &str) -> Result<Vec<Self>, ExchangeRateError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header = lines
+            .next()
+            .ok_or_else(|| ExchangeRateError::InvalidResponse("empty CSV".to_string()))?;
+
+        // The first column is the date, and rows end with a trailing comma
+        let codes = header
+            .split(',')
+            .skip(1)
+            .map(|code| match code.trim() {
+                "" => Ok(None),
+                code => parse_code(code),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut days = lines
+            .map(|line| {
+                let mut fields = line.split(',').map(str::trim);
+                let date = parse_date(fields.next().unwrap_or_default())?;
+
+                let mut rates = HashMap::new();
+                for (code, value) in codes.iter().zip(fields) {
+                    if let Some(code) = code
+                        && !value.is_empty()
+                        && value != "N/A"
+                    {
+                        rates.insert(*code, parse_rate(value)?);
+                    }
+                }
+
+                Ok(Self { date, rates })
+            })
+            .collect::<Result<Vec<_>, ExchangeRateError>>()?;
+
+        sort_newest_first(&mut days)?;
+        Ok(days)
+    }
+
+    /// The rates as a latest rates response for any base currency
+    ///
+    /// Returns `None` if there's no euro rate for `base`.
+    #[must_use]
+    pub fn to_response(&self, base: impl IntoCurrencyCode) -> Option<ExchangeRateResponse> {
+        let base = base.into_currency_code().ok()?;
+        let published = publish_time(self.date);
+        let next_update = publish_time(next_business_day(self.date));
+
+        let mut conversion_rates = self.rates.clone();
+        conversion_rates.insert(euro(), 1.0);
+
+        let response = ExchangeRateResponse {
+            result: "success".to_string(),
+            documentation: DOCUMENTATION_URL.to_string(),
+            terms_of_use: TERMS_OF_USE_URL.to_string(),
+            time_last_update_unix: unix_time(published),
+            time_last_update_utc: published.to_rfc2822(),
+            time_next_update_unix: unix_time(next_update),
+            time_next_update_utc: next_update.to_rfc2822(),
+            base_code: euro(),
+            conversion_rates,
+            provider: Some("ecb".to_string()),
+            consensus: None,
+            stale: false,
+        };
+
+        if base == euro() {
+            Some(response)
+        } else {
+            response.rebase(base)
+        }
+    }
+
+    /// The rates as a historical rates response for any base currency
+    ///
+    /// Returns `None` if there's no euro rate for `base`.
+    #[must_use]
+    pub fn to_historical(&self, base: impl IntoCurrencyCode) -> Option<HistoricalRatesResponse> {
+        let rates = self.to_response(base)?;
+
+        Some(HistoricalRatesResponse {
+            result: rates.result,
+            documentation: rates.documentation,
+            terms_of_use: rates.terms_of_use,
+            year: self.date.year(),
+            month: self.date.month(),
+            day: self.date.day(),
+            base_code: rates.base_code,
+            requested_amount: None,
+            conversion_rates: rates.conversion_rates,
+            conversion_amounts: None,
+            provider: rates.provider,
+        })
+    }
+}
+
+/// Parse a currency code, skipping well-formed codes missing from the registry
+fn parse_code(code: &str) -> Result<Option<CurrencyCode>, ExchangeRateError> {
+    let code = CurrencyCode::parse_well_formed(code)
+        .map_err(|e| ExchangeRateError::InvalidResponse(e.to_string()))?;
+
+    Ok(code.is_known().then_some(code))
+}
+
+fn parse_rate(rate: &str) -> Result<f64, ExchangeRateError> {
+    rate.trim()
+        .parse()
+        .map_err(|_| ExchangeRateError::InvalidResponse(format!("invalid rate '{}'", rate)))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, ExchangeRateError> {
+    let date = date.trim();
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%d %B %Y"))
+        .map_err(|_| ExchangeRateError::InvalidResponse(format!("invalid date '{}'", date)))
+}
+
+fn sort_newest_first(days: &mut [EcbReferenceRates]) -> Result<(), ExchangeRateError> {
+    if days.is_empty() {
+        return Err(ExchangeRateError::InvalidResponse(
+            "no reference rates found".to_string(),
+        ));
+    }
+
+    days.sort_by_key(|day| Reverse(day.date));
+    Ok(())
+}
+
+fn euro() -> CurrencyCode {
+    CurrencyCode::from_ascii(*b"EUR")
+}
+
+fn publish_time(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(PUBLISH_HOUR_UTC, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+}
+
+fn unix_time(time: DateTime<Utc>) -> u64 {
+    u64::try_from(time.timestamp()).unwrap_or_default()
+}
+
+/// The next weekday, ignoring the TARGET holidays when no rates are published
+fn next_business_day(date: NaiveDate) -> NaiveDate {
+    let mut next = date;
+    loop {
+        next = next.succ_opt().unwrap_or(next);
+        if !matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+            return next;
+        }
+    }
+}
+
+/// Euro foreign exchange reference rates from the European Central Bank
+///
+/// The ECB publishes rates for about 30 currencies against the euro around
+/// 16:00 CET on working days. Rates for other base currencies are derived from
+/// the euro rates. No API key is needed.
+///
+/// Historical rates go back to 4 January 1999. Days without rates, such as
+/// weekends and holidays, get the rates of the previous working day, dated
+/// accordingly. Dates whose rates haven't been published yet, such as today
+/// before 16:00 CET, fail with `NoDataAvailable`. Dates in the last few months
+/// are fetched from the ECB's 90 day history, and older ones from its full
+/// history, a file of several megabytes.
+///
+/// To cache the rates, serve the provider through an `ExchangeRateClient`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{EcbProvider, ExchangeRateClient};
+/// use std::sync::Arc;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = ExchangeRateClient::builder()
+///         .provider(Arc::new(EcbProvider::new()))
+///         .build()?;
+///
+///     let rates = client.get_latest_rates("USD").await?;
+///     println!("1 USD = {:?} EUR", rates.get_rate("EUR"));
+///     Ok(())
+/// }
+/// ```
+pub struct EcbProvider {
+    transport: Arc<dyn HttpTransport>,
+    base_url: String,
+}
+
+impl EcbProvider {
+    /// Create a provider fetching from the ECB website with a 30 second timeout
+    #[must_use]
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            transport: Arc::new(ReqwestTransport::new(client)),
+            base_url: DEFAULT_BASE_URL.to_string(),
+        }
+    }
+
+    /// Fetch the rate files through a custom transport
+    #[must_use]
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Fetch the rate files from a mirror of the ECB's `stats/eurofxref` directory
+    #[must_use]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Fetch and parse one of the XML rate files
+    async fn fetch(&self, file: &str) -> Result<Vec<EcbReferenceRates>, ExchangeRateError> {
+        let request = HttpRequest {
+            url: format!("{}/{}", self.base_url.trim_end_matches('/'), file),
+            headers: Vec::new(),
+        };
+
+        let response = self.transport.send(request).await?;
+        if !response.status.is_success() {
+            return Err(ExchangeRateError::HttpError(response.status));
+        }
+
+        EcbReferenceRates::parse_xml(&response.body)
+    }
+
+    /// Fetch the most recent day's rates
+    async fn fetch_daily(&self) -> Result<EcbReferenceRates, ExchangeRateError> {
+        let mut days = self.fetch(DAILY_FILE).await?;
+        // Parsing fails rather than returning no days
+        Ok(days.swap_remove(0))
+    }
+}
+
+impl Default for EcbProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RateProvider for EcbProvider {
+    fn name(&self) -> &str {
+        "ecb"
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        self.fetch_daily()
+            .await?
+            .to_response(base)
+            .ok_or(ExchangeRateError::UnsupportedCode)
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        let daily = self.fetch_daily().await?;
+
+        let mut codes: Vec<_> = daily
+            .rates
+            .keys()
+            .copied()
+            .chain([euro()])
+            .filter_map(|code| Some((code, code.currency()?.name().to_string())))
+            .collect();
+        codes.sort();
+        Ok(codes)
+    }
+
+    async fn historical(
+        &self,
+        base: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let today = Utc::now().date_naive();
+        if date < FIRST_FIX || date > today {
+            return Err(ExchangeRateError::NoDataAvailable);
+        }
+
+        let recent = (today - date).num_days() < RECENT_HISTORY_DAYS;
+        let mut days = self
+            .fetch(if recent {
+                RECENT_HISTORY_FILE
+            } else {
+                FULL_HISTORY_FILE
+            })
+            .await?;
+        if recent && days.iter().all(|day| day.date > date) {
+            days = self.fetch(FULL_HISTORY_FILE).await?;
+        }
+
+        // Until the next working day's rates are published, e.g. today before
+        // 16:00 CET, the newest rates would stand in for rates that are to come
+        if next_business_day(days[0].date) <= date {
+            return Err(ExchangeRateError::NoDataAvailable);
+        }
+
+        // Newest first, so this is the requested day or the working day before it
+        days.iter()
+            .find(|day| day.date <= date)
+            .ok_or(ExchangeRateError::NoDataAvailable)?
+            .to_historical(base)
+            .ok_or(ExchangeRateError::UnsupportedCode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExchangeRateClient, FakeTransport, HttpResponse};
+    use reqwest::StatusCode;
+
+    const DAILY_XML: &str = include_str!("../fixtures/ecb/eurofxref-daily.xml");
+    const HISTORY_XML: &str = include_str!("../fixtures/ecb/eurofxref-hist.xml");
+    const RECENT_HISTORY_XML: &str = include_str!("../fixtures/ecb/eurofxref-hist-90d.xml");
+    const DAILY_CSV: &str = include_str!("../fixtures/ecb/eurofxref.csv");
+    const HISTORY_CSV: &str = include_str!("../fixtures/ecb/eurofxref-hist.csv");
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn code(code: &str) -> CurrencyCode {
+        code.parse().unwrap()
+    }
+
+    fn fake_provider() -> (Arc<FakeTransport>, EcbProvider) {
+        let transport = Arc::new(FakeTransport::new());
+        for (file, body) in [
+            (DAILY_FILE, DAILY_XML),
+            (FULL_HISTORY_FILE, HISTORY_XML),
+            (RECENT_HISTORY_FILE, RECENT_HISTORY_XML),
+        ] {
+            transport.respond(
+                format!("/{}", file),
+                HttpResponse::new(StatusCode::OK, body),
+            );
+        }
+
+        let provider = EcbProvider::new().transport(transport.clone());
+        (transport, provider)
+    }
+
+    #[test]
+    fn test_parse_daily_xml() {
+        let days = EcbReferenceRates::parse_xml(DAILY_XML).unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].date, date(2025, 5, 14));
+        assert_eq!(days[0].rates.len(), 30);
+        assert_eq!(days[0].rates[&code("USD")], 1.1185);
+
+        let eur = days[0].to_response("EUR").unwrap();
+        assert_eq!(eur.base_code, "EUR");
+        assert_eq!(eur.get_rate("EUR"), Some(1.0));
+        assert_eq!(eur.get_rate("JPY"), Some(164.38));
+        assert_eq!(eur.time_last_update_utc, "Wed, 14 May 2025 15:00:00 +0000");
+        assert_eq!(eur.time_next_update_utc, "Thu, 15 May 2025 15:00:00 +0000");
+
+        let usd = days[0].to_response("USD").unwrap();
+        assert_eq!(usd.base_code, "USD");
+        assert!((usd.get_rate("EUR").unwrap() - 1.0 / 1.1185).abs() < 1e-12);
+        assert!((usd.get_rate("GBP").unwrap() - 0.8413 / 1.1185).abs() < 1e-12);
+        assert!((usd.get_rate("USD").unwrap() - 1.0).abs() < 1e-12);
+
+        // No rate for the new base
+        assert!(days[0].to_response("AED").is_none());
+    }
+
+    #[test]
+    fn test_parse_history_xml_and_csv() {
+        let xml = EcbReferenceRates::parse_xml(HISTORY_XML).unwrap();
+        let csv = EcbReferenceRates::parse_csv(HISTORY_CSV).unwrap();
+
+        for days in [&xml, &csv] {
+            assert_eq!(days[0].date, date(2025, 5, 14));
+            assert_eq!(days.last().unwrap().date, date(1999, 1, 4));
+            assert_eq!(days.last().unwrap().rates[&code("GBP")], 0.7111);
+            // The Cyprus pound was replaced by the euro, so isn't in the registry
+            assert!(days.iter().all(|day| day.rates.len() == 3));
+        }
+
+        // The daily CSV has a subset of the daily XML's currencies in this fixture
+        let daily_csv = EcbReferenceRates::parse_csv(DAILY_CSV).unwrap();
+        let daily_xml = EcbReferenceRates::parse_xml(DAILY_XML).unwrap();
+        assert_eq!(daily_csv[0].date, daily_xml[0].date);
+        assert_eq!(daily_csv[0].rates.len(), 11);
+        assert!(
+            daily_csv[0]
+                .rates
+                .iter()
+                .all(|(code, rate)| daily_xml[0].rates[code] == *rate)
+        );
+
+        let historical = csv[2].to_historical("GBP").unwrap();
+        assert_eq!(historical.date(), Some(date(2008, 1, 2)));
+        assert_eq!(historical.base_code, "GBP");
+        assert!((historical.get_rate("USD").unwrap() - 1.4717 / 0.74005).abs() < 1e-12);
+
+        for invalid in ["", "<Cube/>", "<Cube><Cube time='2025-05-14'>"] {
+            assert!(matches!(
+                EcbReferenceRates::parse_xml(invalid),
+                Err(ExchangeRateError::InvalidResponse(_))
+            ));
+        }
+        assert!(matches!(
+            EcbReferenceRates::parse_csv("Date, USD\n2025-05-14, one\n"),
+            Err(ExchangeRateError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_next_update_skips_weekends() {
+        assert_eq!(next_business_day(date(2025, 5, 14)), date(2025, 5, 15));
+        assert_eq!(next_business_day(date(2025, 5, 16)), date(2025, 5, 19));
+        assert_eq!(next_business_day(date(2025, 5, 17)), date(2025, 5, 19));
+    }
+
+    #[tokio::test]
+    async fn test_provider_latest_codes_and_history() {
+        let (transport, provider) = fake_provider();
+
+        let rates = provider.latest(code("CHF")).await.unwrap();
+        assert_eq!(rates.base_code, "CHF");
+        assert_eq!(rates.provider.as_deref(), Some("ecb"));
+        assert!((rates.get_rate("EUR").unwrap() - 1.0 / 0.9387).abs() < 1e-12);
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml"
+        );
+        assert!(matches!(
+            provider.latest(code("AED")).await,
+            Err(ExchangeRateError::UnsupportedCode)
+        ));
+
+        let codes = provider.codes().await.unwrap();
+        assert_eq!(codes.len(), 31);
+        assert!(codes.contains(&(code("EUR"), "Euro".to_string())));
+
+        // A Wednesday gets the rates of the Tuesday before it in this trimmed file
+        let rates = provider
+            .historical(code("USD"), date(1999, 1, 6))
+            .await
+            .unwrap();
+        assert_eq!(rates.date(), Some(date(1999, 1, 5)));
+        assert!((rates.get_rate("JPY").unwrap() - 131.42 / 1.179).abs() < 1e-9);
+        assert!(
+            transport
+                .requests()
+                .last()
+                .unwrap()
+                .url
+                .ends_with(FULL_HISTORY_FILE)
+        );
+
+        for too_early_or_late in [date(1998, 12, 31), date(2200, 1, 1)] {
+            assert!(matches!(
+                provider.historical(code("USD"), too_early_or_late).await,
+                Err(ExchangeRateError::NoDataAvailable)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_errors() {
+        let transport = Arc::new(FakeTransport::new());
+        transport.respond(
+            "/eurofxref-daily.xml",
+            HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, "down for maintenance"),
+        );
+        transport.respond(
+            "/eurofxref-hist.xml",
+            HttpResponse::new(StatusCode::OK, "<html>moved</html>"),
+        );
+        let provider = EcbProvider::new().transport(transport);
+
+        assert!(matches!(
+            provider.latest(code("EUR")).await,
+            Err(ExchangeRateError::HttpError(
+                StatusCode::SERVICE_UNAVAILABLE
+            ))
+        ));
+        assert!(matches!(
+            provider.historical(code("EUR"), date(2000, 1, 3)).await,
+            Err(ExchangeRateError::InvalidResponse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unpublished_rates_not_served() {
+        let (_, provider) = fake_provider();
+        let client = ExchangeRateClient::builder()
+            .provider(Arc::new(provider))
+            .build()
+            .unwrap();
+
+        // The newest rates in the fixture are for Wednesday 14 May 2025, as on
+        // Thursday before that day's rates are published
+        for unpublished in [date(2025, 5, 15), date(2025, 5, 17)] {
+            assert!(matches!(
+                client.get_historical_rates("EUR", unpublished).await,
+                Err(ExchangeRateError::NoDataAvailable)
+            ));
+        }
+
+        let rates = client
+            .get_historical_rates("EUR", date(2025, 5, 14))
+            .await
+            .unwrap();
+        assert_eq!(rates.date(), Some(date(2025, 5, 14)));
+    }
+
+    #[tokio::test]
+    async fn test_client_serves_and_caches_ecb_rates() {
+        let (transport, provider) = fake_provider();
+        let client = ExchangeRateClient::builder()
+            .provider(Arc::new(provider))
+            .build()
+            .unwrap();
+
+        let pair = client.get_pair_conversion("GBP", "USD").await.unwrap();
+        assert!((pair.conversion_rate - 1.1185 / 0.8413).abs() < 1e-12);
+        assert_eq!(pair.provider.as_deref(), Some("ecb"));
+
+        let history = client
+            .get_historical_rates_with_amount("EUR", date(1999, 1, 4), 100.0)
+            .await
+            .unwrap();
+        assert_eq!(history.get_amount("USD"), Some(117.89));
+
+        // Historical rates don't change, so are cached for good. The fixture's
+        // latest rates are long past their next update, so aren't reused.
+        let requests = transport.request_count();
+        client
+            .get_historical_rates_with_amount("EUR", date(1999, 1, 4), 100.0)
+            .await
+            .unwrap();
+        assert_eq!(transport.request_count(), requests);
+    }
+}
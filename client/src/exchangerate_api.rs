@@ -0,0 +1,270 @@
+//! The exchangerate-api.com and open.er-api.com APIs
+
+use crate::cache::{CacheBackend, create_cache_key};
+use crate::limits::{BudgetTracker, TokenBucket};
+use crate::single_flight::SingleFlight;
+use crate::{
+    AuthMethod, CurrencyCode, ExchangeRateError, ExchangeRateResponse, HistoricalRatesResponse,
+    HttpRequest, HttpTransport, NetworkPolicy, PairConversionResponse, RateProvider, RetryPolicy,
+    check_api_response, retry, telemetry,
+};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "decimal")]
+use crate::Decimal;
+
+/// The HTTP API the client serves rates from unless another provider is set
+///
+/// Applies the client's authentication, retry policy, rate limit and request
+/// budget to every request.
+pub(crate) struct ExchangeRateApi {
+    pub(crate) api_key: String,
+    pub(crate) base_url: String,
+    pub(crate) auth_method: AuthMethod,
+    pub(crate) transport: Arc<dyn HttpTransport>,
+    /// Where the request budget is stored, if caching is enabled
+    pub(crate) cache: Option<Arc<dyn CacheBackend>>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) rate_limiter: Option<Arc<TokenBucket>>,
+    pub(crate) budget: Option<Arc<BudgetTracker>>,
+    pub(crate) in_flight: SingleFlight<Result<String, ExchangeRateError>>,
+    pub(crate) network_policy: NetworkPolicy,
+}
+
+impl ExchangeRateApi {
+    /// Check if this is the keyless open access API
+    pub(crate) const fn is_open_access(&self) -> bool {
+        matches!(self.auth_method, AuthMethod::None)
+    }
+
+    /// Fail with `MissingApiKey` for endpoints that open access doesn't provide
+    pub(crate) const fn require_api_key(&self) -> Result<(), ExchangeRateError> {
+        if self.is_open_access() {
+            Err(ExchangeRateError::MissingApiKey)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Constructs the appropriate URL based on the authentication method
+    pub(crate) fn build_url(&self, endpoint: &str, params: &[&str]) -> String {
+        match self.auth_method {
+            AuthMethod::InUrl => {
+                // Include API key in URL
+                let mut url = format!("{}/{}/{}", self.base_url, self.api_key, endpoint);
+                for param in params {
+                    url.push('/');
+                    url.push_str(param);
+                }
+                url
+            }
+            AuthMethod::BearerToken | AuthMethod::None => {
+                // Omit API key from URL
+                let mut url = format!("{}/{}", self.base_url, endpoint);
+                for param in params {
+                    url.push('/');
+                    url.push_str(param);
+                }
+                url
+            }
+        }
+    }
+
+    /// Sends a GET request for an endpoint, applying the configured authentication,
+    /// and returns the response body
+    ///
+    /// API error envelopes are decoded into the matching `ExchangeRateError` variant.
+    /// Concurrent calls for the same request share a single round trip and its result.
+    async fn send_request(
+        &self,
+        endpoint: &str,
+        params: &[&str],
+    ) -> Result<String, ExchangeRateError> {
+        let key = create_cache_key(endpoint, params);
+        if self.network_policy == NetworkPolicy::Offline {
+            return Err(ExchangeRateError::NotCached(key));
+        }
+
+        self.in_flight
+            .run(&key, || self.send_with_retries(endpoint, params))
+            .await
+    }
+
+    /// Sends a request, retrying failed attempts according to the retry policy
+    async fn send_with_retries(
+        &self,
+        endpoint: &str,
+        params: &[&str],
+    ) -> Result<String, ExchangeRateError> {
+        let url = self.build_url(endpoint, params);
+
+        // Quota checks don't count against the API quota, so they're free
+        let counts_against_budget = endpoint != "quota";
+
+        let mut attempt = 1;
+        loop {
+            match self.send_attempt(&url, counts_against_budget).await {
+                Ok(body) => return Ok(body),
+                Err((error, retry_after)) => {
+                    let Some(delay) = self.retry_policy.delay_after(attempt, &error, retry_after)
+                    else {
+                        return Err(error);
+                    };
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Makes a single attempt at a request, returning any `Retry-After` delay the
+    /// server asked for alongside an error
+    async fn send_attempt(
+        &self,
+        url: &str,
+        counts_against_budget: bool,
+    ) -> Result<String, (ExchangeRateError, Option<Duration>)> {
+        self.acquire_request(counts_against_budget)
+            .await
+            .map_err(|e| (e, None))?;
+
+        let mut request = HttpRequest {
+            url: url.to_string(),
+            headers: Vec::new(),
+        };
+
+        // Add authorization header if using bearer token auth
+        if let AuthMethod::BearerToken = self.auth_method {
+            request.headers.push((
+                reqwest::header::AUTHORIZATION.to_string(),
+                format!("Bearer {}", self.api_key),
+            ));
+        }
+
+        let response = match self.transport.send(request).await {
+            Ok(response) => response,
+            Err(err) => {
+                telemetry::http_attempt(url, &self.api_key, Err(&err));
+                return Err((err, None));
+            }
+        };
+        telemetry::http_attempt(url, &self.api_key, Ok(response.status));
+
+        let retry_after = response
+            .header(reqwest::header::RETRY_AFTER.as_str())
+            .and_then(retry::parse_retry_after);
+
+        check_api_response(response.status, &response.body).map_err(|e| (e, retry_after))?;
+
+        Ok(response.body)
+    }
+
+    /// Spends from the request budget and waits for the rate limiter before a request
+    async fn acquire_request(&self, counts_against_budget: bool) -> Result<(), ExchangeRateError> {
+        if let Some(budget) = self.budget.as_ref().filter(|_| counts_against_budget) {
+            match budget.try_spend(self.cache.as_ref()).await {
+                Ok(true) => {}
+                Ok(false) => return Err(ExchangeRateError::BudgetExhausted),
+                Err(err) => {
+                    // The request is still counted in memory, so carry on
+                    telemetry::recovered("Failed to store request budget", &err);
+                }
+            }
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of requests left in this month's budget, or `None` without one
+    pub(crate) async fn remaining_budget(&self) -> Option<u64> {
+        let budget = self.budget.as_ref()?;
+        Some(budget.remaining(self.cache.as_ref()).await)
+    }
+
+    /// Sends a GET request for an endpoint and deserializes the response body
+    pub(crate) async fn fetch<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[&str],
+    ) -> Result<T, ExchangeRateError> {
+        let body = self.send_request(endpoint, params).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[async_trait]
+impl RateProvider for ExchangeRateApi {
+    fn name(&self) -> &str {
+        if self.is_open_access() {
+            "open.er-api.com"
+        } else {
+            "exchangerate-api.com"
+        }
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        self.fetch("latest", &[base.as_str()]).await
+    }
+
+    #[cfg(feature = "decimal")]
+    async fn latest_decimal(
+        &self,
+        base: CurrencyCode,
+    ) -> Result<ExchangeRateResponse<Decimal>, ExchangeRateError> {
+        // Deserialize straight into decimals rather than through f64
+        self.fetch("latest", &[base.as_str()]).await
+    }
+
+    async fn pair(
+        &self,
+        from: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        self.fetch("pair", &[from.as_str(), to.as_str()]).await
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        #[derive(serde::Deserialize)]
+        struct SupportedCodesResponse {
+            supported_codes: Vec<Vec<String>>,
+        }
+
+        self.require_api_key()?;
+
+        let response: SupportedCodesResponse = self.fetch("codes", &[]).await?;
+        Ok(response
+            .supported_codes
+            .into_iter()
+            .filter_map(|code_pair| match code_pair.as_slice() {
+                [code, name, ..] => {
+                    Some((CurrencyCode::parse_well_formed(code).ok()?, name.clone()))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn historical(
+        &self,
+        base: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        self.require_api_key()?;
+
+        let (year, month, day) = (
+            date.year().to_string(),
+            date.month().to_string(),
+            date.day().to_string(),
+        );
+        self.fetch("history", &[base.as_str(), &year, &month, &day])
+            .await
+    }
+}
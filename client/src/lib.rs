@@ -1,21 +1,68 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod cache;
+mod consensus;
+pub mod currency;
+mod currency_code;
+#[cfg(feature = "ecb")]
+mod ecb;
+mod exchangerate_api;
+mod limits;
 mod models;
+mod portfolio;
+mod provider;
+mod rate_table;
+mod retry;
+mod rounding;
+mod scheduler;
+mod single_flight;
+mod telemetry;
+#[cfg(test)]
+mod test_support;
 #[cfg(test)]
 mod tests;
+mod transport;
 
 pub use cache::{CacheBackend, CacheConfig, CachedResponse, InMemoryCache};
-pub use models::{CurrencyCode, ExchangeRateResponse};
+pub use consensus::{Consensus, ConsensusMethod, ConsensusProvider, CurrencyConsensus};
+pub use currency::Currency;
+pub use currency_code::{CurrencyCode, CurrencyCodeError, IntoCurrencyCode};
+pub use limits::{RateLimit, RequestBudget};
+pub use models::{
+    EnrichedPairResponse, ExchangeRateResponse, HistoricalRatesResponse, PairConversionResponse,
+    QuotaResponse, TargetData,
+};
+pub use portfolio::{Portfolio, Position, PositionValuation, Valuation};
+pub use provider::{FallbackProvider, RateProvider};
+pub use rate_table::RateTable;
+pub use retry::RetryPolicy;
+pub use rounding::{RoundingMode, RoundingPolicy, cash_increment};
+pub use scheduler::{RefreshHandle, RefreshScheduler};
+pub use transport::{FakeTransport, HttpRequest, HttpResponse, HttpTransport, ReqwestTransport};
 
 #[cfg(feature = "sqlite-cache")]
 pub use cache::sqlite::SqliteCache;
 
-use cache::create_cache_key;
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+
+#[cfg(feature = "ecb")]
+pub use ecb::{EcbProvider, EcbReferenceRates};
+
+use cache::{Cacheable, create_cache_key};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use exchangerate_api::ExchangeRateApi;
+use limits::{BudgetTracker, TokenBucket};
+use serde::Deserialize;
+use single_flight::SingleFlight;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
+use telemetry::{CacheStatus, RequestSpan};
 use thiserror::Error;
 
 /// Authentication methods supported by the Exchange Rate API
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum AuthMethod {
     /// API key is included in the URL (less secure but simpler)
     /// Example: <https://v6.exchangerate-api.com/v6/YOUR-API-KEY/latest/USD>
@@ -24,18 +71,43 @@ pub enum AuthMethod {
     /// API key is passed as a bearer token in the Authorization header (more secure)
     /// Example: GET <https://v6.exchangerate-api.com/v6/latest/USD>
     /// With header: Authorization: Bearer YOUR-API-KEY
+    ///
+    /// This is the default as it is the more secure method.
+    #[default]
     BearerToken,
+
+    /// No API key, using the free open access endpoint
+    /// Example: GET <https://open.er-api.com/v6/latest/USD>
+    ///
+    /// Only the latest rates are available (pair rates are derived from them),
+    /// rates update once a day, and the terms require attribution.
+    None,
 }
 
-impl Default for AuthMethod {
-    fn default() -> Self {
-        // Default to the more secure method
-        Self::BearerToken
-    }
+/// Whether the client may call the API or only read from its cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkPolicy {
+    /// Serve fresh cache entries and fetch everything else from the API
+    #[default]
+    Online,
+
+    /// Never call the API, serving cache entries even after they've expired
+    ///
+    /// Requests with nothing cached fail with `ExchangeRateError::NotCached`.
+    Offline,
+
+    /// Serve any cache entry, even an expired one, and only call the API on a miss
+    PreferCache,
 }
 
+/// Default base URL of the authenticated API
+const DEFAULT_BASE_URL: &str = "https://v6.exchangerate-api.com/v6";
+
+/// Default base URL of the keyless open access API
+const OPEN_ACCESS_BASE_URL: &str = "https://open.er-api.com/v6";
+
 /// Errors that can occur when using the Exchange Rate API client
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum ExchangeRateError {
     #[error("Missing API key")]
     MissingApiKey,
@@ -55,27 +127,161 @@ pub enum ExchangeRateError {
     #[error("API quota reached")]
     QuotaReached,
 
+    /// The endpoint is not available on the API key's plan
+    #[error("Plan upgrade required")]
+    PlanUpgradeRequired,
+
+    /// The API has no data for the request (e.g. a historical date out of range)
+    #[error("No data available")]
+    NoDataAvailable,
+
+    /// The client's monthly request budget is spent, so the API wasn't called
+    #[error("Monthly request budget exhausted")]
+    BudgetExhausted,
+
+    /// The client is offline and has no cached response for this cache key
+    #[error("No cached response for '{0}' is available offline")]
+    NotCached(String),
+
+    /// The client's rate provider doesn't support the request
+    #[error("Not supported by the rate provider")]
+    NotSupported,
+
+    /// A currency code passed to the client is invalid
+    #[error("{0}")]
+    InvalidCurrencyCode(#[from] CurrencyCodeError),
+
+    /// A custom transport failed to get a response
+    #[error("Transport error: {0}")]
+    TransportError(String),
+
+    /// The API returned an error type this client doesn't know about
+    #[error("API error: {0}")]
+    UnknownApiError(String),
+
+    /// The request couldn't be sent or its response couldn't be read
+    ///
+    /// The error is shared, so that callers coalesced onto one request all get it.
     #[error("HTTP client error: {0}")]
-    HttpClientError(#[from] reqwest::Error),
+    HttpClientError(#[source] Arc<reqwest::Error>),
 
     #[error("HTTP error: {0}")]
     HttpError(reqwest::StatusCode),
 
+    /// A response couldn't be parsed
     #[error("JSON parsing error: {0}")]
-    JsonError(#[from] serde_json::Error),
+    JsonError(#[source] Arc<serde_json::Error>),
+
+    /// A rate provider's response wasn't in the expected format
+    #[error("Invalid provider response: {0}")]
+    InvalidResponse(String),
+
+    /// A decimal calculation overflowed
+    #[error("Arithmetic overflow")]
+    Overflow,
 
     /// A cache error occurred
     #[error("Cache error: {0}")]
     CacheError(#[from] cache::CacheError),
 }
 
+impl From<reqwest::Error> for ExchangeRateError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::HttpClientError(Arc::new(err))
+    }
+}
+
+impl From<serde_json::Error> for ExchangeRateError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::JsonError(Arc::new(err))
+    }
+}
+
+impl ExchangeRateError {
+    /// Map an API `error-type` value to its error variant
+    fn from_api_error_type(error_type: &str) -> Self {
+        match error_type {
+            "unsupported-code" => Self::UnsupportedCode,
+            "malformed-request" => Self::MalformedRequest,
+            "invalid-key" => Self::InvalidKey,
+            "inactive-account" => Self::InactiveAccount,
+            "quota-reached" => Self::QuotaReached,
+            "plan-upgrade-required" => Self::PlanUpgradeRequired,
+            "no-data-available" => Self::NoDataAvailable,
+            other => Self::UnknownApiError(other.to_string()),
+        }
+    }
+
+    /// Check if the error is transient, so the same request may succeed if retried
+    ///
+    /// True for timeouts, connection failures and transport errors, `429 Too Many Requests`,
+    /// `408 Request Timeout` and 5xx server errors.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpClientError(e) => {
+                e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+            }
+            Self::TransportError(_) => true,
+            Self::HttpError(status) => {
+                *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || *status == reqwest::StatusCode::REQUEST_TIMEOUT
+                    || status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the error is about the API key or account, which retrying can't fix
+    pub(crate) const fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingApiKey | Self::InvalidKey | Self::InactiveAccount
+        )
+    }
+}
+
+/// The `result`/`error-type` envelope included in every API response
+#[derive(Deserialize)]
+struct ApiEnvelope {
+    result: String,
+    #[serde(rename = "error-type")]
+    error_type: Option<String>,
+}
+
+/// Check a response for API errors
+///
+/// Error envelopes are decoded even when sent with a non-success status, so that
+/// e.g. a 403 with `"error-type":"invalid-key"` becomes `InvalidKey` rather than a
+/// bare `HttpError`.
+fn check_api_response(status: reqwest::StatusCode, body: &str) -> Result<(), ExchangeRateError> {
+    if let Ok(envelope) = serde_json::from_str::<ApiEnvelope>(body)
+        && envelope.result == "error"
+    {
+        let error_type = envelope.error_type.unwrap_or_default();
+        return Err(ExchangeRateError::from_api_error_type(&error_type));
+    }
+
+    // Check for HTTP errors
+    if !status.is_success() {
+        return Err(ExchangeRateError::HttpError(status));
+    }
+
+    Ok(())
+}
+
+/// Borrow owned path parameters as the `&str` slices requests take
+fn str_params(params: &[String]) -> Vec<&str> {
+    params.iter().map(String::as_str).collect()
+}
+
 /// # Exchange Rate API Client
 ///
 /// A Rust client for the Exchange Rate API (<https://www.exchangerate-api.com/>)
 ///
 /// ## Authentication
 ///
-/// This client supports two authentication methods:
+/// This client supports three authentication methods:
 ///
 /// 1. **Bearer Token Authentication (Default, More Secure)**
 ///    - API key is passed in the Authorization header
@@ -85,6 +291,9 @@ pub enum ExchangeRateError {
 ///    - API key is included directly in the URL
 ///    - Simpler but less secure as the API key may appear in logs
 ///
+/// 3. **Open Access (No API Key)**
+///    - Uses the free, keyless `open.er-api.com` endpoint
+///    - Only latest rates are available, updated once a day
 /// ## Security Considerations
 ///
 /// - Never hardcode your API key in source code
@@ -113,13 +322,19 @@ pub enum ExchangeRateError {
 ///     Ok(())
 /// }
 /// ```
+///
+/// Clones share the cache, rate limiter, request budget and in-flight requests.
+#[derive(Clone)]
 pub struct ExchangeRateClient {
-    api_key: String,
-    base_url: String,
-    auth_method: AuthMethod,
-    http_client: reqwest::Client,
+    api: Arc<ExchangeRateApi>,
+    /// Where rates are fetched from, `api` unless another provider was set
+    provider: Arc<dyn RateProvider>,
+    custom_provider: bool,
     cache: Option<Arc<dyn CacheBackend>>,
     cache_config: CacheConfig,
+    rounding: Option<RoundingPolicy>,
+    single_base: Option<CurrencyCode>,
+    network_policy: NetworkPolicy,
 }
 
 /// Builder for creating an `ExchangeRateClient` with custom configuration
@@ -130,6 +345,14 @@ pub struct ExchangeRateClientBuilder {
     timeout: Option<Duration>,
     cache: Option<Arc<dyn CacheBackend>>,
     cache_config: CacheConfig,
+    rounding: Option<RoundingPolicy>,
+    single_base: Option<CurrencyCode>,
+    retry_policy: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+    request_budget: Option<RequestBudget>,
+    network_policy: NetworkPolicy,
+    transport: Option<Arc<dyn HttpTransport>>,
+    provider: Option<Arc<dyn RateProvider>>,
 }
 
 impl Default for ExchangeRateClientBuilder {
@@ -144,11 +367,19 @@ impl ExchangeRateClientBuilder {
     pub fn new() -> Self {
         Self {
             api_key: None,
-            base_url: Some("https://v6.exchangerate-api.com/v6".to_string()),
+            base_url: None,
             auth_method: AuthMethod::BearerToken, // Default to more secure method
             timeout: Some(Duration::from_secs(30)),
             cache: None,
             cache_config: CacheConfig::default(),
+            rounding: None,
+            single_base: None,
+            retry_policy: RetryPolicy::none(),
+            rate_limit: None,
+            request_budget: None,
+            network_policy: NetworkPolicy::Online,
+            transport: None,
+            provider: None,
         }
     }
 
@@ -166,7 +397,25 @@ impl ExchangeRateClientBuilder {
         self
     }
 
+    /// Use the free, keyless open access endpoint
+    ///
+    /// Equivalent to `.auth_method(AuthMethod::None)`.
+    #[must_use]
+    pub const fn open_access(mut self) -> Self {
+        self.auth_method = AuthMethod::None;
+        self
+    }
+
+    /// Check if the builder is configured for the keyless open access endpoint
+    #[must_use]
+    pub const fn is_open_access(&self) -> bool {
+        matches!(self.auth_method, AuthMethod::None)
+    }
+
     /// Set a custom base URL (useful for testing or if the API URL changes)
+    ///
+    /// Defaults to the authenticated API, or the open access API when using
+    /// `AuthMethod::None`.
     #[must_use]
     pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = Some(base_url.into());
@@ -180,6 +429,28 @@ impl ExchangeRateClientBuilder {
         self
     }
 
+    /// Send requests through a custom transport instead of `reqwest`
+    ///
+    /// The builder's timeout only applies to the default transport. See
+    /// [`FakeTransport`] for testing without a network.
+    #[must_use]
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Serve rates from another provider instead of exchangerate-api.com
+    ///
+    /// The client still applies its cache, network policy and stale handling, so
+    /// e.g. a [`FallbackProvider`] can be cached in a `SqliteCache`. No API key is
+    /// needed. Enriched pairs and quotas are only available from
+    /// exchangerate-api.com, so fail with `NotSupported`.
+    #[must_use]
+    pub fn provider(mut self, provider: Arc<dyn RateProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
     /// Set a cache backend for the client
     ///
     /// # Examples
@@ -211,6 +482,9 @@ impl ExchangeRateClientBuilder {
     /// let cache_config = CacheConfig {
     ///     enabled: true,
     ///     default_ttl: Duration::hours(12),
+    ///     // Keep serving rates for up to a day while the API is down
+    ///     stale_if_error: Some(Duration::days(1)),
+    ///     ..CacheConfig::default()
     /// };
     ///
     /// let client = ExchangeRateClient::builder()
@@ -244,23 +518,133 @@ impl ExchangeRateClientBuilder {
         self
     }
 
+    /// Round amounts returned by `convert` and `convert_decimal` with this policy
+    ///
+    /// By default converted amounts are not rounded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use client::{ExchangeRateClient, RoundingMode, RoundingPolicy};
+    ///
+    /// let client = ExchangeRateClient::builder()
+    ///     .api_key("your-api-key")
+    ///     .rounding(RoundingPolicy::new(RoundingMode::HalfEven))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn rounding(mut self, policy: RoundingPolicy) -> Self {
+        self.rounding = Some(policy);
+        self
+    }
+
+    /// Serve every base currency from the latest rates of a single base
+    ///
+    /// Latest rates, conversions and pair rates for any other base are derived by
+    /// triangulating through `base`, so converting between many currencies costs one
+    /// request (or none while the rates are cached) instead of one per base.
+    /// Triangulated rates may differ slightly from the API's direct rates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use client::ExchangeRateClient;
+    ///
+    /// let client = ExchangeRateClient::builder()
+    ///     .api_key("your-api-key")
+    ///     .single_base("USD".parse().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn single_base(mut self, base: CurrencyCode) -> Self {
+        self.single_base = Some(base);
+        self
+    }
+
+    /// Retry failed requests according to a policy
+    ///
+    /// By default each request is attempted once. See [`RetryPolicy`] for the
+    /// backoff and which errors are retried.
+    #[must_use]
+    pub const fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Limit how fast requests are sent to the API
+    ///
+    /// Requests over the limit wait for their turn. Retries count as requests.
+    #[must_use]
+    pub const fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Limit the number of requests sent to the API each month
+    ///
+    /// See [`RequestBudget`] for what happens once it's spent.
+    #[must_use]
+    pub const fn request_budget(mut self, budget: RequestBudget) -> Self {
+        self.request_budget = Some(budget);
+        self
+    }
+
+    /// Choose when the client may call the API
+    ///
+    /// By default the client is online. Expired entries served because of the
+    /// policy are marked as stale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use client::{ExchangeRateClient, NetworkPolicy};
+    ///
+    /// // Only ever read rates cached on an earlier run
+    /// let client = ExchangeRateClient::builder()
+    ///     .api_key("your-api-key")
+    ///     .network_policy(NetworkPolicy::Offline)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn network_policy(mut self, policy: NetworkPolicy) -> Self {
+        self.network_policy = policy;
+        self
+    }
+
     /// Build the client with the configured settings
     ///
     /// # Errors
     ///
-    /// Returns an error if the API key is not provided or if the HTTP client cannot be created
+    /// Returns an error if the API key is not provided (unless using open access
+    /// or another provider) or if the HTTP client cannot be created
     pub fn build(self) -> Result<ExchangeRateClient, ExchangeRateError> {
-        let api_key = self.api_key.ok_or(ExchangeRateError::MissingApiKey)?;
+        let (api_key, default_base_url) = match self.auth_method {
+            AuthMethod::None => (String::new(), OPEN_ACCESS_BASE_URL),
+            AuthMethod::InUrl | AuthMethod::BearerToken if self.provider.is_some() => {
+                (self.api_key.unwrap_or_default(), DEFAULT_BASE_URL)
+            }
+            AuthMethod::InUrl | AuthMethod::BearerToken => (
+                self.api_key.ok_or(ExchangeRateError::MissingApiKey)?,
+                DEFAULT_BASE_URL,
+            ),
+        };
 
-        // Create HTTP client with appropriate timeout
-        let mut client_builder = reqwest::Client::builder();
-        if let Some(timeout) = self.timeout {
-            client_builder = client_builder.timeout(timeout);
-        }
+        // Create HTTP client with appropriate timeout, unless a transport was provided
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut client_builder = reqwest::Client::builder();
+                if let Some(timeout) = self.timeout {
+                    client_builder = client_builder.timeout(timeout);
+                }
 
-        let http_client = client_builder
-            .build()
-            .map_err(ExchangeRateError::HttpClientError)?;
+                let http_client = client_builder.build().map_err(ExchangeRateError::from)?;
+                Arc::new(ReqwestTransport::new(http_client)) as Arc<dyn HttpTransport>
+            }
+        };
 
         // Set up default in-memory cache if caching is enabled but no cache backend was provided
         let cache = if self.cache_config.enabled {
@@ -282,15 +666,34 @@ impl ExchangeRateClientBuilder {
             None
         };
 
-        Ok(ExchangeRateClient {
+        let api = Arc::new(ExchangeRateApi {
             api_key,
             base_url: self
                 .base_url
-                .unwrap_or_else(|| "https://v6.exchangerate-api.com/v6".to_string()),
+                .unwrap_or_else(|| default_base_url.to_string()),
             auth_method: self.auth_method,
-            http_client,
+            transport,
+            cache: cache.clone(),
+            retry_policy: self.retry_policy,
+            rate_limiter: self
+                .rate_limit
+                .map(|limit| Arc::new(TokenBucket::new(limit))),
+            budget: self
+                .request_budget
+                .map(|budget| Arc::new(BudgetTracker::new(budget))),
+            in_flight: SingleFlight::default(),
+            network_policy: self.network_policy,
+        });
+
+        Ok(ExchangeRateClient {
+            provider: self.provider.clone().unwrap_or_else(|| api.clone()),
+            custom_provider: self.provider.is_some(),
+            api,
             cache,
             cache_config: self.cache_config,
+            rounding: self.rounding,
+            single_base: self.single_base,
+            network_policy: self.network_policy,
         })
     }
 }
@@ -302,100 +705,265 @@ impl ExchangeRateClient {
         ExchangeRateClientBuilder::new()
     }
 
-    /// Constructs the appropriate URL based on the authentication method
-    fn build_url(&self, endpoint: &str, params: &[&str]) -> String {
-        match self.auth_method {
-            AuthMethod::InUrl => {
-                // Include API key in URL
-                format!(
-                    "{}/{}/{}/{}",
-                    self.base_url,
-                    self.api_key,
-                    endpoint,
-                    params.join("/")
-                )
+    /// Check if the client is using the keyless open access endpoint
+    #[must_use]
+    pub fn is_open_access(&self) -> bool {
+        self.api.is_open_access()
+    }
+
+    /// Get exchangerate-api.com for endpoints only it provides, or fail with
+    /// `NotSupported` when serving another provider
+    const fn require_exchangerate_api(&self) -> Result<&Arc<ExchangeRateApi>, ExchangeRateError> {
+        if self.custom_provider {
+            Err(ExchangeRateError::NotSupported)
+        } else {
+            Ok(&self.api)
+        }
+    }
+
+    /// Get the number of requests left in this month's budget, or `None` without one
+    pub async fn remaining_budget(&self) -> Option<u64> {
+        self.api.remaining_budget().await
+    }
+
+    /// Returns the cache backend if caching is enabled
+    fn active_cache(&self) -> Option<&Arc<dyn CacheBackend>> {
+        if self.cache_config.enabled {
+            self.cache.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a cache entry, including an expired one, with its expiration time
+    ///
+    /// Misses and cache failures yield `None` so the caller falls back to the API.
+    async fn read_cache<T: Cacheable>(&self, cache_key: &str) -> Option<(T, DateTime<Utc>)> {
+        let cache = self.active_cache()?;
+
+        match T::read(cache.as_ref(), cache_key).await {
+            Ok(entry) => Some(entry),
+            Err(cache::CacheError::NotFound) | Err(cache::CacheError::Expired) => {
+                // Cache miss or expired, continue to fetch from API
+                None
             }
-            AuthMethod::BearerToken => {
-                // Omit API key from URL
-                format!("{}/{}/{}", self.base_url, endpoint, params.join("/"))
+            Err(err) => {
+                // Report the cache error but continue with the API request
+                telemetry::recovered("Failed to read cached response", &err);
+                None
             }
         }
     }
 
-    /// Get latest exchange rates for a base currency
-    ///
-    /// # Errors
+    /// The cache key for a request, prefixed with the name of any custom provider
+    /// so that its entries don't mix with the API's in a shared cache
+    fn cache_key(&self, endpoint: &str, params: &[&str]) -> String {
+        let key = create_cache_key(endpoint, params);
+        if self.custom_provider {
+            format!("{}:{}", self.provider.name(), key)
+        } else {
+            key
+        }
+    }
+
+    /// Awaits a fetch from the provider or API and stores the response in the cache
+    async fn fetch_and_cache<T: Cacheable>(
+        &self,
+        cache_key: &str,
+        fetch: impl Future<Output = Result<T, ExchangeRateError>>,
+    ) -> Result<T, ExchangeRateError> {
+        let response = fetch.await?;
+
+        if let Some(cache) = self.active_cache()
+            && let Err(err) = response.write(cache.as_ref(), cache_key).await
+        {
+            // Report the cache error but continue
+            telemetry::recovered("Failed to cache response", &err);
+        }
+
+        Ok(response)
+    }
+
+    /// Gets a response from the cache, or with `fetch` on a miss
     ///
-    /// Returns an error if the API request fails, the response cannot be parsed,
-    /// or the API returns an error response
-    pub async fn get_latest_rates(
+    /// Depending on the network policy, cache configuration and request budget, an
+    /// expired entry may be returned, marked as stale, while it's refreshed in the
+    /// background or in place of an error from the API.
+    async fn get_or_fetch<T, F, Fut>(
         &self,
-        base_code: &str,
-    ) -> Result<ExchangeRateResponse, ExchangeRateError> {
-        // Create a cache key for this request
-        let cache_key = create_cache_key("latest", &[base_code]);
+        endpoint: &str,
+        params: &[&str],
+        fetch: F,
+    ) -> Result<T, ExchangeRateError>
+    where
+        T: Cacheable,
+        F: FnOnce(Self) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, ExchangeRateError>> + Send + 'static,
+    {
+        let span = RequestSpan::new(endpoint, params, self.name());
+        span.track(
+            &self.api.api_key,
+            self.serve_cached(&span, endpoint, params, fetch),
+        )
+        .await
+    }
 
-        // Try to get from cache first if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                match cache.get_exchange_rate(&cache_key).await {
-                    Ok(cached) => {
-                        // Return the cached response
-                        return Ok(cached.response);
-                    }
-                    Err(cache::CacheError::NotFound) | Err(cache::CacheError::Expired) => {
-                        // Cache miss or expired, continue to fetch from API
-                    }
-                    Err(err) => {
-                        // Log cache error but continue with API request
-                        eprintln!("Cache error: {}", err);
-                    }
-                }
+    /// The body of [`get_or_fetch`](Self::get_or_fetch), recording the cache status
+    async fn serve_cached<T, F, Fut>(
+        &self,
+        span: &RequestSpan,
+        endpoint: &str,
+        params: &[&str],
+        fetch: F,
+    ) -> Result<T, ExchangeRateError>
+    where
+        T: Cacheable,
+        F: FnOnce(Self) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, ExchangeRateError>> + Send + 'static,
+    {
+        let cache_key = self.cache_key(endpoint, params);
+        let cached = self.read_cache::<T>(&cache_key).await;
+
+        if let Some((response, expires_at)) = &cached {
+            if Utc::now() <= *expires_at {
+                span.cache(CacheStatus::Hit);
+                return Ok(response.clone());
             }
-        }
-
-        // Cache miss or caching disabled, fetch from API
-        let url = self.build_url("latest", &[base_code]);
 
-        let mut request_builder = self.http_client.get(&url);
+            // Offline and cache-first clients make do with what they have
+            if self.network_policy != NetworkPolicy::Online {
+                span.cache(CacheStatus::Stale);
+                return Ok(response.clone().into_stale());
+            }
 
-        // Add authorization header if using bearer token auth
-        if let AuthMethod::BearerToken = self.auth_method {
-            request_builder = request_builder.header(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", self.api_key),
-            );
+            // Serve the expired entry while a fresh copy is fetched
+            if CacheConfig::is_within(self.cache_config.stale_while_revalidate, *expires_at) {
+                span.cache(CacheStatus::Stale);
+                self.spawn_refresh(endpoint, params, fetch);
+                return Ok(response.clone().into_stale());
+            }
         }
 
-        let response = request_builder
-            .send()
-            .await
-            .map_err(ExchangeRateError::HttpClientError)?;
+        span.cache(if self.active_cache().is_some() {
+            CacheStatus::Miss
+        } else {
+            CacheStatus::Bypass
+        });
 
-        // Check for HTTP errors
-        if !response.status().is_success() {
-            return Err(ExchangeRateError::HttpError(response.status()));
+        if self.network_policy == NetworkPolicy::Offline {
+            return Err(ExchangeRateError::NotCached(cache_key));
         }
 
-        // Parse the response
-        let exchange_rate_response = response
-            .json::<ExchangeRateResponse>()
-            .await
-            .map_err(ExchangeRateError::HttpClientError)?;
-
-        // Store in cache if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                let cached_response =
-                    cache::CachedResponse::new_with_api_expiration(exchange_rate_response.clone());
-                if let Err(err) = cache.set_exchange_rate(&cache_key, cached_response).await {
-                    // Log cache error but continue
-                    eprintln!("Failed to cache response: {}", err);
+        match self.fetch_and_cache(&cache_key, fetch(self.clone())).await {
+            Ok(response) => Ok(response),
+            Err(err) => match cached {
+                Some((response, expires_at)) if self.serves_stale_on(&err, expires_at) => {
+                    span.cache(CacheStatus::Stale);
+                    Ok(response.into_stale())
                 }
+                _ => Err(err),
+            },
+        }
+    }
+
+    /// Refreshes a cache entry in a background task, in a request span of its own
+    fn spawn_refresh<T, F, Fut>(&self, endpoint: &str, params: &[&str], fetch: F)
+    where
+        T: Cacheable,
+        F: FnOnce(Self) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, ExchangeRateError>> + Send + 'static,
+    {
+        let client = self.clone();
+        let cache_key = self.cache_key(endpoint, params);
+        let span = RequestSpan::new(endpoint, params, self.name());
+        span.cache(CacheStatus::Bypass);
+
+        tokio::spawn(async move {
+            let refresh = client.fetch_and_cache(&cache_key, fetch(client.clone()));
+            if let Err(err) = span.track(&client.api.api_key, refresh).await {
+                telemetry::recovered("Failed to refresh cached response", &err);
+            }
+        });
+    }
+
+    /// Check if an entry that expired at `expires_at` should be served in place of
+    /// an error from the API
+    fn serves_stale_on(&self, error: &ExchangeRateError, expires_at: DateTime<Utc>) -> bool {
+        match error {
+            ExchangeRateError::BudgetExhausted => self
+                .api
+                .budget
+                .as_ref()
+                .is_some_and(|budget| budget.budget().is_serving_stale()),
+            error => {
+                error.is_retryable()
+                    && CacheConfig::is_within(self.cache_config.stale_if_error, expires_at)
             }
         }
+    }
+
+    /// Get latest exchange rates for a base currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub async fn get_latest_rates(
+        &self,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        let base_code = base_code.into_currency_code()?;
+
+        // In single-base mode, derive other bases from the single base's rates
+        match self.single_base {
+            Some(single_base) if single_base != base_code => self
+                .fetch_latest_rates(single_base)
+                .await?
+                .rebase(base_code)
+                .ok_or(ExchangeRateError::UnsupportedCode),
+            _ => self.fetch_latest_rates(base_code).await,
+        }
+    }
+
+    /// Get latest exchange rates for a base currency as a [`RateTable`] of cross rates
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub async fn get_rate_table(
+        &self,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<RateTable, ExchangeRateError> {
+        self.get_latest_rates(base_code).await.map(RateTable::from)
+    }
 
-        Ok(exchange_rate_response)
+    /// Fetches latest rates for a base currency, using the cache if possible
+    async fn fetch_latest_rates(
+        &self,
+        base_code: CurrencyCode,
+    ) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        self.get_or_fetch("latest", &[base_code.as_str()], move |client| async move {
+            client.provider.latest(base_code).await
+        })
+        .await
+    }
+
+    /// Fetches latest rates for a base currency from the API, replacing any cached copy
+    pub(crate) async fn refresh_latest_rates(
+        &self,
+        base_code: CurrencyCode,
+    ) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        let params = [base_code.as_str()];
+        let cache_key = self.cache_key("latest", &params);
+
+        let span = RequestSpan::new("latest", &params, self.name());
+        span.cache(CacheStatus::Bypass);
+        span.track(
+            &self.api.api_key,
+            self.fetch_and_cache(&cache_key, self.provider.latest(base_code)),
+        )
+        .await
     }
 
     /// Convert an amount from one currency to another
@@ -407,9 +975,12 @@ impl ExchangeRateClient {
     pub async fn convert(
         &self,
         amount: f64,
-        from_currency: &str,
-        to_currency: &str,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
     ) -> Result<f64, ExchangeRateError> {
+        let from_currency = from_currency.into_currency_code()?;
+        let to_currency = to_currency.into_currency_code()?;
+
         // Get the latest rates with from_currency as base
         let rates = self.get_latest_rates(from_currency).await?;
 
@@ -418,107 +989,272 @@ impl ExchangeRateClient {
             .get_rate(to_currency)
             .ok_or(ExchangeRateError::UnsupportedCode)?;
 
-        // Calculate the converted amount
-        Ok(amount * rate)
+        // Calculate the converted amount, rounding it if a policy is configured
+        let converted = amount * rate;
+        Ok(self
+            .rounding
+            .map_or(converted, |policy| policy.round(converted, to_currency)))
     }
 
-    /// Get pair conversion rate (direct conversion between two currencies)
+    /// Get latest exchange rates for a base currency as exact decimals
+    ///
+    /// Rates are deserialized straight from the API's JSON into [`Decimal`] without
+    /// passing through `f64`. Decimal responses are cached separately from the
+    /// `f64` responses returned by [`get_latest_rates`](Self::get_latest_rates).
     ///
     /// # Errors
     ///
     /// Returns an error if the API request fails, the response cannot be parsed,
     /// or the API returns an error response
-    pub async fn get_pair_conversion(
+    #[cfg(feature = "decimal")]
+    pub async fn get_latest_rates_decimal(
         &self,
-        from_currency: &str,
-        to_currency: &str,
-    ) -> Result<f64, ExchangeRateError> {
-        // Define the response structure at the beginning of the function
-        #[derive(serde::Deserialize, serde::Serialize, Clone)]
-        struct PairConversionResponse {
-            conversion_rate: f64,
+        base_code: impl IntoCurrencyCode,
+    ) -> Result<ExchangeRateResponse<Decimal>, ExchangeRateError> {
+        let base_code = base_code.into_currency_code()?;
+
+        // In single-base mode, derive other bases from the single base's rates
+        match self.single_base {
+            Some(single_base) if single_base != base_code => self
+                .fetch_latest_rates_decimal(single_base)
+                .await?
+                .rebase(base_code)
+                .ok_or(ExchangeRateError::UnsupportedCode),
+            _ => self.fetch_latest_rates_decimal(base_code).await,
         }
+    }
 
-        // Create a cache key for this request
-        let cache_key = create_cache_key("pair", &[from_currency, to_currency]);
+    /// Fetches latest rates for a base currency as exact decimals, using the cache
+    /// if possible
+    #[cfg(feature = "decimal")]
+    async fn fetch_latest_rates_decimal(
+        &self,
+        base_code: CurrencyCode,
+    ) -> Result<ExchangeRateResponse<Decimal>, ExchangeRateError> {
+        // Decimal responses are cached separately from the f64 ones
+        let params = [base_code.as_str(), "decimal"];
 
-        // Try to get from cache first if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                match cache.get_raw(&cache_key).await {
-                    Ok((json, _, _)) => {
-                        // Parse the cached JSON
-                        match serde_json::from_str::<PairConversionResponse>(&json) {
-                            Ok(response) => {
-                                return Ok(response.conversion_rate);
-                            }
-                            Err(err) => {
-                                eprintln!("Failed to parse cached response: {}", err);
-                            }
-                        }
-                    }
-                    Err(cache::CacheError::NotFound) | Err(cache::CacheError::Expired) => {
-                        // Cache miss or expired, continue to fetch from API
-                    }
-                    Err(err) => {
-                        // Log cache error but continue with API request
-                        eprintln!("Cache error: {}", err);
-                    }
-                }
-            }
-        }
+        self.get_or_fetch("latest", &params, move |client| async move {
+            client.provider.latest_decimal(base_code).await
+        })
+        .await
+    }
 
-        // Cache miss or caching disabled, fetch from API
-        let url = self.build_url("pair", &[from_currency, to_currency]);
+    /// Convert an amount from one currency to another using exact decimal arithmetic
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// the API returns an error response, or if the target currency is not supported
+    #[cfg(feature = "decimal")]
+    pub async fn convert_decimal(
+        &self,
+        amount: Decimal,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Result<Decimal, ExchangeRateError> {
+        let from_currency = from_currency.into_currency_code()?;
+        let to_currency = to_currency.into_currency_code()?;
 
-        let mut request_builder = self.http_client.get(&url);
+        // Get the latest rates with from_currency as base
+        let rates = self.get_latest_rates_decimal(from_currency).await?;
 
-        // Add authorization header if using bearer token auth
-        if let AuthMethod::BearerToken = self.auth_method {
-            request_builder = request_builder.header(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", self.api_key),
-            );
-        }
+        // Calculate the converted amount, rounding it if a policy is configured
+        let converted = rates
+            .convert_from_base(amount, to_currency)
+            .ok_or(ExchangeRateError::UnsupportedCode)?;
+        Ok(self.rounding.map_or(converted, |policy| {
+            policy.round_decimal(converted, to_currency)
+        }))
+    }
 
-        let response = request_builder
-            .send()
+    /// Get pair conversion rate (direct conversion between two currencies)
+    ///
+    /// With open access the rate is taken from the latest rates for `from_currency`.
+    /// Cached entries expire when the API publishes its next rates update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub async fn get_pair_conversion(
+        &self,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        let from_currency = from_currency.into_currency_code()?;
+        let to_currency = to_currency.into_currency_code()?;
+        self.fetch_pair(from_currency, to_currency, None).await
+    }
+
+    /// Get pair conversion rate along with `amount` converted into the target currency
+    ///
+    /// The converted value is returned in [`PairConversionResponse::conversion_result`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response
+    pub async fn get_pair_conversion_with_amount(
+        &self,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+        amount: f64,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        let from_currency = from_currency.into_currency_code()?;
+        let to_currency = to_currency.into_currency_code()?;
+        self.fetch_pair(from_currency, to_currency, Some(amount))
             .await
-            .map_err(ExchangeRateError::HttpClientError)?;
+    }
 
-        // Check for HTTP errors
-        if !response.status().is_success() {
-            return Err(ExchangeRateError::HttpError(response.status()));
+    /// Shared implementation of the `pair` endpoint
+    async fn fetch_pair(
+        &self,
+        from_currency: CurrencyCode,
+        to_currency: CurrencyCode,
+        amount: Option<f64>,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        // Open access has no pair endpoint, and single-base mode avoids it, so derive
+        // the rate from the latest rates
+        if self.is_open_access() || self.single_base.is_some() {
+            let rates = self.get_latest_rates(from_currency).await?;
+            return PairConversionResponse::from_latest(rates, to_currency, amount)
+                .ok_or(ExchangeRateError::UnsupportedCode);
         }
 
-        // Parse the response to get the conversion rate
-        let pair_response = response
-            .json::<PairConversionResponse>()
-            .await
-            .map_err(ExchangeRateError::HttpClientError)?;
+        let mut params = vec![from_currency.to_string(), to_currency.to_string()];
+        if let Some(amount) = amount {
+            params.push(amount.to_string());
+        }
 
-        // Store in cache if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                let json = match serde_json::to_string(&pair_response) {
-                    Ok(json) => json,
-                    Err(err) => {
-                        eprintln!("Failed to serialize response: {}", err);
-                        return Ok(pair_response.conversion_rate);
-                    }
-                };
+        self.get_or_fetch("pair", &str_params(&params), move |client| async move {
+            let mut pair = client.provider.pair(from_currency, to_currency).await?;
+            pair.conversion_result = amount.map(|amount| amount * pair.conversion_rate);
+            Ok(pair)
+        })
+        .await
+    }
 
-                let cached_at = chrono::Utc::now();
-                let expires_at = cached_at + self.cache_config.default_ttl;
+    /// Get the conversion rate between two currencies along with metadata about
+    /// the target currency (locale, name, display symbol and flag)
+    ///
+    /// Cached entries expire when the API publishes its next rates update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (enriched data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub async fn get_enriched_pair(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        target_code: impl IntoCurrencyCode,
+    ) -> Result<EnrichedPairResponse, ExchangeRateError> {
+        let base_code = base_code.into_currency_code()?;
+        let target_code = target_code.into_currency_code()?;
+        self.require_exchangerate_api()?.require_api_key()?;
+
+        let params = [base_code.as_str(), target_code.as_str()];
+        self.get_or_fetch("enriched", &params, move |client| async move {
+            client
+                .api
+                .fetch("enriched", &[base_code.as_str(), target_code.as_str()])
+                .await
+        })
+        .await
+    }
 
-                if let Err(err) = cache.set_raw(&cache_key, json, cached_at, expires_at).await {
-                    // Log cache error but continue
-                    eprintln!("Failed to cache response: {}", err);
-                }
-            }
+    /// Get historical exchange rates for a base currency on a specific date
+    ///
+    /// Historical data never changes, so cached entries never expire.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (historical data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub async fn get_historical_rates(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let base_code = base_code.into_currency_code()?;
+        self.fetch_historical(base_code, date, None).await
+    }
+
+    /// Get historical exchange rates for a base currency on a specific date,
+    /// with every rate multiplied by `amount`
+    ///
+    /// The converted values are returned in
+    /// [`HistoricalRatesResponse::conversion_amounts`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response (historical data requires a paid plan).
+    /// Returns `MissingApiKey` when using open access.
+    pub async fn get_historical_rates_with_amount(
+        &self,
+        base_code: impl IntoCurrencyCode,
+        date: NaiveDate,
+        amount: f64,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let base_code = base_code.into_currency_code()?;
+        self.fetch_historical(base_code, date, Some(amount)).await
+    }
+
+    /// Shared implementation of the `history` endpoint
+    async fn fetch_historical(
+        &self,
+        base_code: CurrencyCode,
+        date: NaiveDate,
+        amount: Option<f64>,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let mut params = vec![
+            base_code.to_string(),
+            date.year().to_string(),
+            date.month().to_string(),
+            date.day().to_string(),
+        ];
+        if let Some(amount) = amount {
+            params.push(amount.to_string());
         }
 
-        Ok(pair_response.conversion_rate)
+        self.get_or_fetch("history", &str_params(&params), move |client| async move {
+            let mut rates = client.provider.historical(base_code, date).await?;
+            if let Some(amount) = amount {
+                rates.requested_amount = Some(amount);
+                rates.conversion_amounts = Some(
+                    rates
+                        .conversion_rates
+                        .iter()
+                        .map(|(code, rate)| (*code, rate * amount))
+                        .collect(),
+                );
+            }
+            Ok(rates)
+        })
+        .await
+    }
+
+    /// Get the request quota for the API key
+    ///
+    /// Quota responses change with every request, so they are never cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API request fails, the response cannot be parsed,
+    /// or the API returns an error response.
+    /// Returns `MissingApiKey` when using open access.
+    pub async fn get_quota(&self) -> Result<QuotaResponse, ExchangeRateError> {
+        let api = self.require_exchangerate_api()?;
+        api.require_api_key()?;
+
+        // Quotas change with every request, so they're never cached
+        let span = RequestSpan::new("quota", &[], self.name());
+        span.cache(CacheStatus::Bypass);
+        span.track(&api.api_key, api.fetch::<QuotaResponse>("quota", &[]))
+            .await
     }
 
     /// Get supported currency codes
@@ -526,125 +1262,45 @@ impl ExchangeRateClient {
     /// # Errors
     ///
     /// Returns an error if the API request fails, the response cannot be parsed,
-    /// or the API returns an error response
-    pub async fn get_supported_codes(&self) -> Result<Vec<(String, String)>, ExchangeRateError> {
+    /// or the API returns an error response.
+    /// Returns `MissingApiKey` when using open access.
+    pub async fn get_supported_codes(
+        &self,
+    ) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
         // Define the response structure at the beginning of the function
         #[derive(serde::Deserialize, serde::Serialize, Clone)]
         struct SupportedCodesResponse {
             supported_codes: Vec<Vec<String>>,
         }
 
-        // Create a cache key for this request
-        let cache_key = create_cache_key("codes", &[]);
-
-        // Try to get from cache first if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                match cache.get_raw(&cache_key).await {
-                    Ok((json, _, _)) => {
-                        // Parse the cached JSON
-                        match serde_json::from_str::<SupportedCodesResponse>(&json) {
-                            Ok(cached) => {
-                                // Convert the cached nested Vec<Vec<String>> to Vec<(String, String)>
-                                let codes = cached
-                                    .supported_codes
-                                    .into_iter()
-                                    .filter_map(|code_pair| {
-                                        if code_pair.len() >= 2 {
-                                            Some((code_pair[0].clone(), code_pair[1].clone()))
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
-                                return Ok(codes);
-                            }
-                            Err(err) => {
-                                eprintln!("Failed to parse cached response: {}", err);
-                            }
-                        }
-                    }
-                    Err(cache::CacheError::NotFound) | Err(cache::CacheError::Expired) => {
-                        // Cache miss or expired, continue to fetch from API
-                    }
-                    Err(err) => {
-                        // Log cache error but continue with API request
-                        eprintln!("Cache error: {}", err);
-                    }
-                }
+        // Currency codes rarely change, so cache for a longer time (1 week)
+        impl Cacheable for SupportedCodesResponse {
+            fn expires_at(&self) -> DateTime<Utc> {
+                Utc::now() + chrono::Duration::weeks(1)
             }
         }
 
-        // Cache miss or caching disabled, fetch from API
-        let url = self.build_url("codes", &[]);
-
-        let mut request_builder = self.http_client.get(&url);
-
-        // Add authorization header if using bearer token auth
-        if let AuthMethod::BearerToken = self.auth_method {
-            request_builder = request_builder.header(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", self.api_key),
-            );
-        }
-
-        let response = request_builder
-            .send()
-            .await
-            .map_err(ExchangeRateError::HttpClientError)?;
-
-        // Check for HTTP errors
-        if !response.status().is_success() {
-            return Err(ExchangeRateError::HttpError(response.status()));
-        }
-
-        // Parse the response to get the supported codes
-        let codes_response = response
-            .json::<SupportedCodesResponse>()
-            .await
-            .map_err(ExchangeRateError::HttpClientError)?;
-
-        // Store in cache if caching is enabled
-        if self.cache_config.enabled {
-            if let Some(cache) = &self.cache {
-                let json = match serde_json::to_string(&codes_response) {
-                    Ok(json) => json,
-                    Err(err) => {
-                        eprintln!("Failed to serialize response: {}", err);
-                        // Continue with the conversion without caching
-                        let codes = codes_response
-                            .supported_codes
-                            .into_iter()
-                            .filter_map(|code_pair| {
-                                if code_pair.len() >= 2 {
-                                    Some((code_pair[0].clone(), code_pair[1].clone()))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        return Ok(codes);
-                    }
-                };
-
-                // Currency codes rarely change, so cache for a longer time (1 week)
-                let cached_at = chrono::Utc::now();
-                let expires_at = cached_at + chrono::Duration::weeks(1);
-
-                if let Err(err) = cache.set_raw(&cache_key, json, cached_at, expires_at).await {
-                    // Log cache error but continue
-                    eprintln!("Failed to cache response: {}", err);
-                }
-            }
-        }
+        let codes_response: SupportedCodesResponse = self
+            .get_or_fetch("codes", &[], |client| async move {
+                let supported_codes = client
+                    .provider
+                    .codes()
+                    .await?
+                    .into_iter()
+                    .map(|(code, name)| vec![code.to_string(), name])
+                    .collect();
+                Ok(SupportedCodesResponse { supported_codes })
+            })
+            .await?;
 
-        // Convert the nested Vec<Vec<String>> to Vec<(String, String)>
+        // Convert the nested Vec<Vec<String>> to Vec<(CurrencyCode, String)>
         let codes = codes_response
             .supported_codes
             .into_iter()
             .filter_map(|code_pair| {
                 if code_pair.len() >= 2 {
-                    Some((code_pair[0].clone(), code_pair[1].clone()))
+                    let code = CurrencyCode::parse_well_formed(&code_pair[0]).ok()?;
+                    Some((code, code_pair[1].clone()))
                 } else {
                     None
                 }
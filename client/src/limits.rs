@@ -0,0 +1,356 @@
+//! Client-side rate limiting and monthly request budgets
+
+use crate::cache::{CacheBackend, CacheError};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// A limit on how fast the client sends requests, enforced with a token bucket
+///
+/// Up to `requests` requests can be sent in a burst, after which requests are
+/// spaced out evenly so that no more than `requests` are sent per `per`. Requests
+/// over the limit wait rather than fail.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ExchangeRateClient, RateLimit};
+///
+/// let client = ExchangeRateClient::builder()
+///     .api_key("your-api-key")
+///     .rate_limit(RateLimit::per_minute(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests per `per`
+    ///
+    /// A limit of zero requests is treated as one.
+    #[must_use]
+    pub const fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            requests: if requests == 0 { 1 } else { requests },
+            per,
+        }
+    }
+
+    /// Allow `requests` requests per second
+    #[must_use]
+    pub const fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute
+    #[must_use]
+    pub const fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// The number of requests allowed per period, which is also the burst size
+    #[must_use]
+    pub const fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    /// The period
+    #[must_use]
+    pub const fn per(&self) -> Duration {
+        self.per
+    }
+}
+
+/// The state of a token bucket enforcing a `RateLimit`
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    /// Available tokens, negative when requests are queued, and when they were counted
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new((f64::from(limit.requests), Instant::now())),
+        }
+    }
+
+    /// Take a token, waiting until one is available
+    pub(crate) async fn acquire(&self) {
+        let capacity = f64::from(self.limit.requests);
+        let refill_per_sec = capacity / self.limit.per.as_secs_f64().max(f64::EPSILON);
+
+        let wait = {
+            let mut state = self.state.lock().await;
+            let (tokens, counted_at) = &mut *state;
+
+            let now = Instant::now();
+            let refilled = now.duration_since(*counted_at).as_secs_f64() * refill_per_sec;
+            *tokens = (*tokens + refilled).min(capacity) - 1.0;
+            *counted_at = now;
+
+            // A negative balance is the queue of requests ahead of this one
+            (*tokens < 0.0).then(|| Duration::from_secs_f64(-*tokens / refill_per_sec))
+        };
+
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// A monthly limit on the number of requests sent to the API
+///
+/// Once the budget is spent, requests fail with
+/// [`ExchangeRateError::BudgetExhausted`](crate::ExchangeRateError::BudgetExhausted)
+/// without calling the API, or are served from expired cache entries when
+/// [`serve_stale`](Self::serve_stale) is enabled. Quota checks don't count, since
+/// the API doesn't count them either.
+///
+/// The number of requests used is stored in the client's cache backend, so with a
+/// persistent backend such as `SqliteCache` it survives restarts.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ExchangeRateClient, RequestBudget};
+///
+/// // Leave headroom below a 1,500 request quota that refreshes on the 14th
+/// let client = ExchangeRateClient::builder()
+///     .api_key("your-api-key")
+///     .request_budget(RequestBudget::monthly(1_400).refresh_day(14).serve_stale(true))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestBudget {
+    limit: u64,
+    refresh_day: u32,
+    serve_stale: bool,
+}
+
+impl RequestBudget {
+    /// Allow `limit` requests per month, refreshing on the 1st
+    #[must_use]
+    pub const fn monthly(limit: u64) -> Self {
+        Self {
+            limit,
+            refresh_day: 1,
+            serve_stale: false,
+        }
+    }
+
+    /// Refresh the budget on a day of the month, e.g. to match the API quota's
+    /// `refresh_day_of_month`
+    ///
+    /// Days after the 28th are treated as the 28th, so that every month has one.
+    #[must_use]
+    pub const fn refresh_day(mut self, day: u32) -> Self {
+        self.refresh_day = if day < 1 {
+            1
+        } else if day > 28 {
+            28
+        } else {
+            day
+        };
+        self
+    }
+
+    /// Serve expired cache entries instead of failing once the budget is spent
+    ///
+    /// Requests with no cache entry still fail with `BudgetExhausted`.
+    #[must_use]
+    pub const fn serve_stale(mut self, serve_stale: bool) -> Self {
+        self.serve_stale = serve_stale;
+        self
+    }
+
+    /// The number of requests allowed per month
+    #[must_use]
+    pub const fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Check if expired cache entries are served once the budget is spent
+    #[must_use]
+    pub const fn is_serving_stale(&self) -> bool {
+        self.serve_stale
+    }
+
+    /// The first day of the budget period containing `today`
+    fn period_start(&self, today: NaiveDate) -> NaiveDate {
+        let this_month = today.with_day(self.refresh_day).unwrap_or(today);
+        if today >= this_month {
+            this_month
+        } else {
+            this_month
+                .checked_sub_months(Months::new(1))
+                .unwrap_or(this_month)
+        }
+    }
+}
+
+/// Cache key under which the budget state is stored
+const BUDGET_CACHE_KEY: &str = "budget:monthly";
+
+/// Requests used in a budget period, as stored in the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct BudgetState {
+    period_start: NaiveDate,
+    used: u64,
+}
+
+/// Tracks requests against a `RequestBudget`
+pub(crate) struct BudgetTracker {
+    budget: RequestBudget,
+    /// The state, loaded from the cache on first use
+    state: Mutex<Option<BudgetState>>,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(budget: RequestBudget) -> Self {
+        Self {
+            budget,
+            state: Mutex::new(None),
+        }
+    }
+
+    pub(crate) const fn budget(&self) -> &RequestBudget {
+        &self.budget
+    }
+
+    /// Count a request against the budget, returning `false` if it's spent
+    pub(crate) async fn try_spend(
+        &self,
+        cache: Option<&Arc<dyn CacheBackend>>,
+    ) -> Result<bool, CacheError> {
+        let mut state = self.state.lock().await;
+        let current = self.current_state(&mut state, cache).await;
+
+        if current.used >= self.budget.limit {
+            return Ok(false);
+        }
+
+        let spent = BudgetState {
+            used: current.used + 1,
+            ..current
+        };
+        *state = Some(spent);
+
+        // Persist the new count so that it survives restarts
+        if let Some(cache) = cache {
+            let json = serde_json::to_string(&spent)
+                .map_err(|e| CacheError::Serialization(e.to_string()))?;
+            let expires_at = self.period_end(spent.period_start);
+            cache
+                .set_raw(BUDGET_CACHE_KEY, json, Utc::now(), expires_at)
+                .await?;
+        }
+
+        Ok(true)
+    }
+
+    /// The number of requests left in the current period
+    pub(crate) async fn remaining(&self, cache: Option<&Arc<dyn CacheBackend>>) -> u64 {
+        let mut state = self.state.lock().await;
+        let current = self.current_state(&mut state, cache).await;
+
+        self.budget.limit.saturating_sub(current.used)
+    }
+
+    /// The state for the current period, loading it from the cache if needed
+    async fn current_state(
+        &self,
+        state: &mut Option<BudgetState>,
+        cache: Option<&Arc<dyn CacheBackend>>,
+    ) -> BudgetState {
+        let period_start = self.budget.period_start(Utc::now().date_naive());
+
+        if state.is_none()
+            && let Some(cache) = cache
+            && let Ok((json, _, _)) = cache.get_raw(BUDGET_CACHE_KEY).await
+        {
+            *state = serde_json::from_str(&json).ok();
+        }
+
+        // A new period starts from zero
+        match *state {
+            Some(state) if state.period_start == period_start => state,
+            _ => BudgetState {
+                period_start,
+                used: 0,
+            },
+        }
+    }
+
+    /// When the period starting on `period_start` ends
+    fn period_end(&self, period_start: NaiveDate) -> DateTime<Utc> {
+        period_start
+            .checked_add_months(Months::new(1))
+            .and_then(|end| end.and_hms_opt(0, 0, 0))
+            .map(|end| end.and_utc())
+            .unwrap_or_else(crate::cache::never_expires)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::InMemoryCache;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_budget_periods() {
+        let budget = RequestBudget::monthly(100).refresh_day(14);
+        assert_eq!(budget.period_start(date("2025-05-14")), date("2025-05-14"));
+        assert_eq!(budget.period_start(date("2025-05-31")), date("2025-05-14"));
+        assert_eq!(budget.period_start(date("2025-05-13")), date("2025-04-14"));
+        assert_eq!(budget.period_start(date("2025-01-01")), date("2024-12-14"));
+
+        assert_eq!(RequestBudget::monthly(100).refresh_day(31).refresh_day, 28);
+    }
+
+    #[tokio::test]
+    async fn test_budget_persists_in_cache() {
+        let cache: Arc<dyn CacheBackend> = Arc::new(InMemoryCache::new());
+        let budget = RequestBudget::monthly(2);
+
+        let tracker = BudgetTracker::new(budget);
+        assert!(tracker.try_spend(Some(&cache)).await.unwrap());
+        assert_eq!(tracker.remaining(Some(&cache)).await, 1);
+
+        // A new tracker, as after a restart, picks up the count from the cache
+        let tracker = BudgetTracker::new(budget);
+        assert!(tracker.try_spend(Some(&cache)).await.unwrap());
+        assert!(!tracker.try_spend(Some(&cache)).await.unwrap());
+        assert_eq!(tracker.remaining(Some(&cache)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_spaces_requests() {
+        let bucket = TokenBucket::new(RateLimit::new(2, Duration::from_millis(200)));
+        let start = Instant::now();
+
+        // The burst goes through immediately
+        bucket.acquire().await;
+        bucket.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // Then one request every 100ms
+        bucket.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        bucket.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
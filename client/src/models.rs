@@ -1,9 +1,18 @@
+use crate::consensus::Consensus;
+use crate::currency_code::{CurrencyCode, IntoCurrencyCode};
+use crate::rounding::RoundingPolicy;
+use chrono::NaiveDate;
+#[cfg(feature = "decimal")]
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Represents the response from the Exchange Rate API
+///
+/// `R` is the type of the exchange rates: `f64` by default, or
+/// [`Decimal`](rust_decimal::Decimal) for exact arithmetic with the `decimal` feature.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExchangeRateResponse {
+pub struct ExchangeRateResponse<R = f64> {
     /// Indicates if the API request was successful
     pub result: String,
 
@@ -32,33 +41,517 @@ pub struct ExchangeRateResponse {
 
     /// The base currency code used for the exchange rates
     #[serde(rename = "base_code")]
-    pub base_code: String,
+    pub base_code: CurrencyCode,
 
     /// Map of currency codes to their exchange rates relative to the base currency
-    #[serde(rename = "conversion_rates")]
-    pub conversion_rates: HashMap<String, f64>,
+    ///
+    /// The open access endpoint names this field `rates`.
+    #[serde(rename = "conversion_rates", alias = "rates")]
+    pub conversion_rates: HashMap<CurrencyCode, R>,
+
+    /// The rate provider that answered, when fetched through a
+    /// [`RateProvider`](crate::RateProvider) (not part of the API response)
+    ///
+    /// Serialized as `answered_by`, as open access responses have a `provider`
+    /// key of their own holding the API's homepage.
+    #[serde(
+        rename = "answered_by",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub provider: Option<String>,
+
+    /// How the rates were agreed, when aggregated by a
+    /// [`ConsensusProvider`](crate::ConsensusProvider) (not part of the API response)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<Consensus>,
+
+    /// Whether the response was served from an expired cache entry, e.g. because
+    /// the API couldn't be reached (not part of the API response)
+    #[serde(skip)]
+    pub stale: bool,
+}
+
+/// Exact decimal versions of the conversion methods
+///
+/// Every calculation is done in decimal arithmetic, so there is no binary floating
+/// point rounding drift. Methods return `None` if a currency is missing or the
+/// calculation overflows.
+#[cfg(feature = "decimal")]
+impl ExchangeRateResponse<Decimal> {
+    /// Get the exchange rate for a specific currency
+    #[must_use]
+    pub fn get_rate(&self, currency_code: impl IntoCurrencyCode) -> Option<Decimal> {
+        let currency_code = currency_code.into_currency_code().ok()?;
+        self.conversion_rates.get(&currency_code).copied()
+    }
+
+    /// Convert an amount from the base currency to another currency
+    #[must_use]
+    pub fn convert_from_base(
+        &self,
+        amount: Decimal,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Option<Decimal> {
+        self.get_rate(to_currency)
+            .and_then(|rate| amount.checked_mul(rate))
+    }
+
+    /// Convert an amount from one currency to another
+    #[must_use]
+    pub fn convert(
+        &self,
+        amount: Decimal,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Option<Decimal> {
+        let from_currency = from_currency.into_currency_code().ok()?;
+        if from_currency == self.base_code {
+            return self.convert_from_base(amount, to_currency);
+        }
+
+        let from_rate = self.get_rate(from_currency)?;
+        let to_rate = self.get_rate(to_currency)?;
+
+        // Multiply before dividing to keep as much precision as possible
+        amount.checked_mul(to_rate)?.checked_div(from_rate)
+    }
+
+    /// Re-express the rates relative to another currency in the response
+    ///
+    /// Returns `None` if there is no non-zero rate for `new_base`. Any
+    /// [`consensus`](Self::consensus) is dropped, as it describes the original base.
+    #[must_use]
+    pub fn rebase(&self, new_base: impl IntoCurrencyCode) -> Option<Self> {
+        let new_base = new_base.into_currency_code().ok()?;
+        let base_rate = self.get_rate(new_base).filter(|rate| !rate.is_zero())?;
+
+        let mut conversion_rates: HashMap<_, _> = self
+            .conversion_rates
+            .iter()
+            .filter_map(|(code, rate)| Some((*code, rate.checked_div(base_rate)?)))
+            .collect();
+        conversion_rates
+            .entry(self.base_code)
+            .or_insert(Decimal::ONE.checked_div(base_rate)?);
+
+        Some(Self {
+            result: self.result.clone(),
+            documentation: self.documentation.clone(),
+            terms_of_use: self.terms_of_use.clone(),
+            time_last_update_unix: self.time_last_update_unix,
+            time_last_update_utc: self.time_last_update_utc.clone(),
+            time_next_update_unix: self.time_next_update_unix,
+            time_next_update_utc: self.time_next_update_utc.clone(),
+            base_code: new_base,
+            conversion_rates,
+            provider: self.provider.clone(),
+            consensus: None,
+            stale: self.stale,
+        })
+    }
+
+    /// Convert an amount from one currency to another, rounding the result in the
+    /// target currency with the given policy
+    #[must_use]
+    pub fn convert_rounded(
+        &self,
+        amount: Decimal,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+        policy: RoundingPolicy,
+    ) -> Option<Decimal> {
+        let to_currency = to_currency.into_currency_code().ok()?;
+        self.convert(amount, from_currency, to_currency)
+            .map(|converted| policy.round_decimal(converted, to_currency))
+    }
+}
+
+/// Compatibility conversion to the `f64` API
+#[cfg(feature = "decimal")]
+impl From<ExchangeRateResponse<Decimal>> for ExchangeRateResponse {
+    fn from(response: ExchangeRateResponse<Decimal>) -> Self {
+        Self {
+            result: response.result,
+            documentation: response.documentation,
+            terms_of_use: response.terms_of_use,
+            time_last_update_unix: response.time_last_update_unix,
+            time_last_update_utc: response.time_last_update_utc,
+            time_next_update_unix: response.time_next_update_unix,
+            time_next_update_utc: response.time_next_update_utc,
+            base_code: response.base_code,
+            conversion_rates: response
+                .conversion_rates
+                .into_iter()
+                .filter_map(|(code, rate)| rate.to_f64().map(|rate| (code, rate)))
+                .collect(),
+            provider: response.provider,
+            consensus: response.consensus,
+            stale: response.stale,
+        }
+    }
+}
+
+/// Conversion from the `f64` API, e.g. for rates from a custom
+/// [`RateProvider`](crate::RateProvider)
+///
+/// Rates that aren't finite are dropped.
+#[cfg(feature = "decimal")]
+impl From<ExchangeRateResponse> for ExchangeRateResponse<Decimal> {
+    fn from(response: ExchangeRateResponse) -> Self {
+        Self {
+            result: response.result,
+            documentation: response.documentation,
+            terms_of_use: response.terms_of_use,
+            time_last_update_unix: response.time_last_update_unix,
+            time_last_update_utc: response.time_last_update_utc,
+            time_next_update_unix: response.time_next_update_unix,
+            time_next_update_utc: response.time_next_update_utc,
+            base_code: response.base_code,
+            conversion_rates: response
+                .conversion_rates
+                .into_iter()
+                .filter_map(|(code, rate)| Decimal::try_from(rate).ok().map(|rate| (code, rate)))
+                .collect(),
+            provider: response.provider,
+            consensus: response.consensus,
+            stale: response.stale,
+        }
+    }
+}
+
+/// Represents the response from the historical data (`history`) endpoint
+///
+/// When an amount is requested, the API returns `conversion_amounts` (the amount
+/// converted into every currency) instead of `conversion_rates`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricalRatesResponse {
+    /// Indicates if the API request was successful
+    pub result: String,
+
+    /// URL to the API documentation
+    pub documentation: String,
+
+    /// URL to the terms of use
+    pub terms_of_use: String,
+
+    /// Year of the requested date
+    pub year: i32,
+
+    /// Month of the requested date (1-12)
+    pub month: u32,
+
+    /// Day of the requested date (1-31)
+    pub day: u32,
+
+    /// The base currency code used for the exchange rates
+    pub base_code: CurrencyCode,
+
+    /// The amount requested, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_amount: Option<f64>,
+
+    /// Map of currency codes to their exchange rates relative to the base currency
+    #[serde(default)]
+    pub conversion_rates: HashMap<CurrencyCode, f64>,
+
+    /// Map of currency codes to the requested amount converted into each currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion_amounts: Option<HashMap<CurrencyCode, f64>>,
+
+    /// The rate provider that answered, when fetched through a
+    /// [`RateProvider`](crate::RateProvider) (not part of the API response)
+    #[serde(
+        rename = "answered_by",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub provider: Option<String>,
+}
+
+impl HistoricalRatesResponse {
+    /// The date the rates apply to
+    #[must_use]
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month, self.day)
+    }
+
+    /// Get the historical exchange rate for a specific currency
+    ///
+    /// When the response was requested with an amount, the rate is derived from
+    /// the converted amount.
+    #[must_use]
+    pub fn get_rate(&self, currency_code: impl IntoCurrencyCode) -> Option<f64> {
+        let currency_code = currency_code.into_currency_code().ok()?;
+        if let Some(rate) = self.conversion_rates.get(&currency_code) {
+            return Some(*rate);
+        }
+
+        let amount = self.requested_amount.filter(|amount| *amount != 0.0)?;
+        self.get_amount(currency_code).map(|value| value / amount)
+    }
+
+    /// Get the requested amount converted into a specific currency
+    #[must_use]
+    pub fn get_amount(&self, currency_code: impl IntoCurrencyCode) -> Option<f64> {
+        let currency_code = currency_code.into_currency_code().ok()?;
+        self.conversion_amounts
+            .as_ref()
+            .and_then(|amounts| amounts.get(&currency_code).copied())
+    }
+}
+
+/// Represents the response from the pair conversion (`pair`) endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairConversionResponse {
+    /// Indicates if the API request was successful
+    pub result: String,
+
+    /// URL to the API documentation
+    pub documentation: String,
+
+    /// URL to the terms of use
+    pub terms_of_use: String,
+
+    /// Unix timestamp of when rates were last updated
+    pub time_last_update_unix: u64,
+
+    /// UTC timestamp of when rates were last updated
+    pub time_last_update_utc: String,
+
+    /// Unix timestamp of when rates will next be updated
+    pub time_next_update_unix: u64,
+
+    /// UTC timestamp of when rates will next be updated
+    pub time_next_update_utc: String,
+
+    /// The base currency code
+    pub base_code: CurrencyCode,
+
+    /// The target currency code
+    pub target_code: CurrencyCode,
+
+    /// Exchange rate from the base currency to the target currency
+    pub conversion_rate: f64,
+
+    /// The requested amount converted into the target currency, if an amount was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion_result: Option<f64>,
+
+    /// The rate provider that answered, when fetched through a
+    /// [`RateProvider`](crate::RateProvider) (not part of the API response)
+    #[serde(
+        rename = "answered_by",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub provider: Option<String>,
+
+    /// Whether the response was served from an expired cache entry, e.g. because
+    /// the API couldn't be reached (not part of the API response)
+    #[serde(skip)]
+    pub stale: bool,
+}
+
+impl PairConversionResponse {
+    /// Derive a pair conversion from latest rates, converting `amount` if given
+    ///
+    /// Returns `None` if there is no rate for `target_code`.
+    #[must_use]
+    pub fn from_latest(
+        rates: ExchangeRateResponse,
+        target_code: CurrencyCode,
+        amount: Option<f64>,
+    ) -> Option<Self> {
+        let conversion_rate = rates.get_rate(target_code)?;
+
+        Some(Self {
+            result: rates.result,
+            documentation: rates.documentation,
+            terms_of_use: rates.terms_of_use,
+            time_last_update_unix: rates.time_last_update_unix,
+            time_last_update_utc: rates.time_last_update_utc,
+            time_next_update_unix: rates.time_next_update_unix,
+            time_next_update_utc: rates.time_next_update_utc,
+            base_code: rates.base_code,
+            target_code,
+            conversion_rate,
+            conversion_result: amount.map(|amount| amount * conversion_rate),
+            provider: rates.provider,
+            stale: rates.stale,
+        })
+    }
+}
+
+/// Represents the response from the enriched pair (`enriched`) endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichedPairResponse {
+    /// Indicates if the API request was successful
+    pub result: String,
+
+    /// URL to the API documentation
+    pub documentation: String,
+
+    /// URL to the terms of use
+    pub terms_of_use: String,
+
+    /// Unix timestamp of when rates were last updated
+    pub time_last_update_unix: u64,
+
+    /// UTC timestamp of when rates were last updated
+    pub time_last_update_utc: String,
+
+    /// Unix timestamp of when rates will next be updated
+    pub time_next_update_unix: u64,
+
+    /// UTC timestamp of when rates will next be updated
+    pub time_next_update_utc: String,
+
+    /// The base currency code
+    pub base_code: CurrencyCode,
+
+    /// The target currency code
+    pub target_code: CurrencyCode,
+
+    /// Exchange rate from the base currency to the target currency
+    pub conversion_rate: f64,
+
+    /// Metadata describing the target currency
+    pub target_data: TargetData,
+
+    /// Whether the response was served from an expired cache entry, e.g. because
+    /// the API couldn't be reached (not part of the API response)
+    #[serde(skip)]
+    pub stale: bool,
 }
 
-/// Currency code type alias for better readability
-pub type CurrencyCode = String;
+impl EnrichedPairResponse {
+    /// Split into the plain pair conversion and the target currency metadata
+    #[must_use]
+    pub fn into_parts(self) -> (PairConversionResponse, TargetData) {
+        let pair = PairConversionResponse {
+            result: self.result,
+            documentation: self.documentation,
+            terms_of_use: self.terms_of_use,
+            time_last_update_unix: self.time_last_update_unix,
+            time_last_update_utc: self.time_last_update_utc,
+            time_next_update_unix: self.time_next_update_unix,
+            time_next_update_utc: self.time_next_update_utc,
+            base_code: self.base_code,
+            target_code: self.target_code,
+            conversion_rate: self.conversion_rate,
+            conversion_result: None,
+            provider: None,
+            stale: self.stale,
+        };
+
+        (pair, self.target_data)
+    }
+}
+
+/// Metadata about a target currency returned by the enriched pair endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetData {
+    /// Locale the currency belongs to (e.g. "Japan")
+    pub locale: String,
+
+    /// ISO 3166 two-letter country code (e.g. "JP")
+    pub two_letter_code: String,
+
+    /// Full currency name (e.g. "Japanese Yen")
+    pub currency_name: String,
+
+    /// Short currency name (e.g. "Yen")
+    pub currency_name_short: String,
+
+    /// Display symbol as comma-separated hexadecimal Unicode code points (e.g. "00A5")
+    pub display_symbol: String,
+
+    /// URL of the locale's flag image
+    pub flag_url: String,
+}
+
+impl TargetData {
+    /// Decode `display_symbol` into the printable currency symbol
+    ///
+    /// Returns `None` if any code point is not valid hexadecimal Unicode.
+    #[must_use]
+    pub fn symbol(&self) -> Option<String> {
+        self.display_symbol
+            .split(',')
+            .map(|code_point| {
+                u32::from_str_radix(code_point.trim(), 16)
+                    .ok()
+                    .and_then(char::from_u32)
+            })
+            .collect()
+    }
+}
+
+/// Represents the response from the request quota (`quota`) endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaResponse {
+    /// Indicates if the API request was successful
+    pub result: String,
+
+    /// URL to the API documentation
+    pub documentation: String,
+
+    /// URL to the terms of use
+    pub terms_of_use: String,
+
+    /// Number of requests allowed per month by the current plan
+    pub plan_quota: u64,
+
+    /// Number of requests remaining until the quota refreshes
+    pub requests_remaining: u64,
+
+    /// Day of the month on which the quota refreshes
+    pub refresh_day_of_month: u32,
+}
+
+impl QuotaResponse {
+    /// Number of requests already used in the current period
+    #[must_use]
+    pub fn requests_used(&self) -> u64 {
+        self.plan_quota.saturating_sub(self.requests_remaining)
+    }
+
+    /// Check if the remaining requests have fallen below a threshold
+    #[must_use]
+    pub fn is_below(&self, threshold: u64) -> bool {
+        self.requests_remaining < threshold
+    }
+}
 
 /// Extension methods for `ExchangeRateResponse`
 impl ExchangeRateResponse {
     /// Get the exchange rate for a specific currency
     #[must_use]
-    pub fn get_rate(&self, currency_code: &str) -> Option<f64> {
-        self.conversion_rates.get(currency_code).copied()
+    pub fn get_rate(&self, currency_code: impl IntoCurrencyCode) -> Option<f64> {
+        let currency_code = currency_code.into_currency_code().ok()?;
+        self.conversion_rates.get(&currency_code).copied()
     }
 
     /// Convert an amount from the base currency to another currency
     #[must_use]
-    pub fn convert_from_base(&self, amount: f64, to_currency: &str) -> Option<f64> {
+    pub fn convert_from_base(
+        &self,
+        amount: f64,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Option<f64> {
         self.get_rate(to_currency).map(|rate| amount * rate)
     }
 
     /// Convert an amount from one currency to another
     #[must_use]
-    pub fn convert(&self, amount: f64, from_currency: &str, to_currency: &str) -> Option<f64> {
+    pub fn convert(
+        &self,
+        amount: f64,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+    ) -> Option<f64> {
+        let from_currency = from_currency.into_currency_code().ok()?;
         if from_currency == self.base_code {
             return self.convert_from_base(amount, to_currency);
         }
@@ -69,11 +562,62 @@ impl ExchangeRateResponse {
         // Convert to base currency first, then to target currency
         Some(amount / from_rate * to_rate)
     }
+
+    /// Re-express the rates relative to another currency in the response
+    ///
+    /// Returns `None` if there is no positive rate for `new_base`. Any
+    /// [`consensus`](Self::consensus) is dropped, as it describes the original base.
+    /// See [`RateTable`](crate::RateTable) for cross rates without rebuilding a response.
+    #[must_use]
+    pub fn rebase(&self, new_base: impl IntoCurrencyCode) -> Option<Self> {
+        let new_base = new_base.into_currency_code().ok()?;
+        let base_rate = self.get_rate(new_base).filter(|rate| *rate > 0.0)?;
+
+        let mut conversion_rates: HashMap<_, _> = self
+            .conversion_rates
+            .iter()
+            .map(|(code, rate)| (*code, rate / base_rate))
+            .collect();
+        conversion_rates
+            .entry(self.base_code)
+            .or_insert(base_rate.recip());
+
+        Some(Self {
+            result: self.result.clone(),
+            documentation: self.documentation.clone(),
+            terms_of_use: self.terms_of_use.clone(),
+            time_last_update_unix: self.time_last_update_unix,
+            time_last_update_utc: self.time_last_update_utc.clone(),
+            time_next_update_unix: self.time_next_update_unix,
+            time_next_update_utc: self.time_next_update_utc.clone(),
+            base_code: new_base,
+            conversion_rates,
+            provider: self.provider.clone(),
+            consensus: None,
+            stale: self.stale,
+        })
+    }
+
+    /// Convert an amount from one currency to another, rounding the result in the
+    /// target currency with the given policy
+    #[must_use]
+    pub fn convert_rounded(
+        &self,
+        amount: f64,
+        from_currency: impl IntoCurrencyCode,
+        to_currency: impl IntoCurrencyCode,
+        policy: RoundingPolicy,
+    ) -> Option<f64> {
+        let to_currency = to_currency.into_currency_code().ok()?;
+        self.convert(amount, from_currency, to_currency)
+            .map(|converted| policy.round(converted, to_currency))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rounding::RoundingMode;
 
     #[test]
     fn test_deserialize_sample_response() {
@@ -112,5 +656,201 @@ mod tests {
                 .map(|v| (v * 100.0).round() / 100.0),
             Some(16480.08)
         );
+
+        // Rounded conversions use the target currency's minor unit
+        let policy = RoundingPolicy::new(RoundingMode::HalfEven);
+        assert_eq!(
+            response.convert_rounded(100.0, "EUR", "JPY", policy),
+            Some(16480.0)
+        );
+        assert_eq!(
+            response.convert_rounded(12.345, "USD", "GBP", policy),
+            Some(9.31)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_historical_response() {
+        let sample_json = r#"{
+            "result":"success",
+            "documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "year":2015,
+            "month":2,
+            "day":22,
+            "base_code":"USD",
+            "requested_amount":4.00,
+            "conversion_amounts":{
+                "AUD":5.682,
+                "EUR":3.524
+            }
+        }"#;
+
+        let response: HistoricalRatesResponse = serde_json::from_str(sample_json).unwrap();
+
+        assert_eq!(response.date(), NaiveDate::from_ymd_opt(2015, 2, 22));
+        assert!(response.conversion_rates.is_empty());
+        assert_eq!(response.get_amount("EUR"), Some(3.524));
+        assert_eq!(response.get_rate("EUR"), Some(0.881));
+    }
+
+    #[test]
+    fn test_deserialize_enriched_response() {
+        let sample_json = r#"{
+            "result":"success",
+            "documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "time_last_update_unix":1585872397,
+            "time_last_update_utc":"Fri, 02 Apr 2020 00:06:37 +0000",
+            "time_next_update_unix":1585959987,
+            "time_next_update_utc":"Sat, 03 Apr 2020 00:26:27 +0000",
+            "base_code":"GBP",
+            "target_code":"AUD",
+            "conversion_rate":2.0508,
+            "target_data":{
+                "locale":"Australia",
+                "two_letter_code":"AU",
+                "currency_name":"Australian Dollar",
+                "currency_name_short":"Dollar",
+                "display_symbol":"0041,0024",
+                "flag_url":"https://www.exchangerate-api.com/img/docs/flags/au.gif"
+            }
+        }"#;
+
+        let response: EnrichedPairResponse = serde_json::from_str(sample_json).unwrap();
+
+        assert_eq!(response.target_code, "AUD");
+        assert_eq!(response.conversion_rate, 2.0508);
+        assert_eq!(response.target_data.currency_name, "Australian Dollar");
+        assert_eq!(response.target_data.symbol().as_deref(), Some("A$"));
+    }
+
+    #[test]
+    fn test_deserialize_quota_response() {
+        let sample_json = r#"{
+            "result":"success",
+            "documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "plan_quota":30000,
+            "requests_remaining":25623,
+            "refresh_day_of_month":17
+        }"#;
+
+        let response: QuotaResponse = serde_json::from_str(sample_json).unwrap();
+
+        assert_eq!(response.plan_quota, 30000);
+        assert_eq!(response.requests_used(), 4377);
+        assert_eq!(response.refresh_day_of_month, 17);
+        assert!(response.is_below(30000));
+        assert!(!response.is_below(1000));
+    }
+
+    #[test]
+    fn test_deserialize_open_access_response() {
+        let sample_json = r#"{
+            "result":"success",
+            "provider":"https://www.exchangerate-api.com",
+            "documentation":"https://www.exchangerate-api.com/docs/free",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "time_last_update_unix":1747180951,
+            "time_last_update_utc":"Wed, 14 May 2025 00:02:31 +0000",
+            "time_next_update_unix":1747268521,
+            "time_next_update_utc":"Thu, 15 May 2025 00:22:01 +0000",
+            "time_eol_unix":0,
+            "base_code":"USD",
+            "rates":{
+                "USD":1,
+                "EUR":0.8958,
+                "GBP":0.7536
+            }
+        }"#;
+
+        let response: ExchangeRateResponse = serde_json::from_str(sample_json).unwrap();
+
+        assert_eq!(response.base_code, "USD");
+        assert_eq!(response.get_rate("EUR"), Some(0.8958));
+        assert_eq!(response.conversion_rates.len(), 3);
+        // The API's own provider key isn't mistaken for the provider that answered
+        assert_eq!(response.provider, None);
+    }
+
+    #[test]
+    fn test_deserialize_pair_response() {
+        let sample_json = r#"{
+            "result":"success",
+            "documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "time_last_update_unix":1585267200,
+            "time_last_update_utc":"Fri, 27 Mar 2020 00:00:00 +0000",
+            "time_next_update_unix":1585353700,
+            "time_next_update_utc":"Sat, 28 Mar 2020 00:00:00 +0000",
+            "base_code":"EUR",
+            "target_code":"GBP",
+            "conversion_rate":0.8412,
+            "conversion_result":5.8884
+        }"#;
+
+        let response: PairConversionResponse = serde_json::from_str(sample_json).unwrap();
+
+        assert_eq!(response.base_code, "EUR");
+        assert_eq!(response.target_code, "GBP");
+        assert_eq!(response.conversion_rate, 0.8412);
+        assert_eq!(response.conversion_result, Some(5.8884));
+        assert_eq!(response.time_next_update_unix, 1585353700);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_deserialize_decimal_response() {
+        use std::str::FromStr;
+
+        let sample_json = r#"{
+            "result":"success",
+            "documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "time_last_update_unix":1747180802,
+            "time_last_update_utc":"Wed, 14 May 2025 00:00:02 +0000",
+            "time_next_update_unix":1747267202,
+            "time_next_update_utc":"Thu, 15 May 2025 00:00:02 +0000",
+            "base_code":"USD",
+            "conversion_rates":{
+                "USD":1,
+                "EUR":0.1,
+                "GBP":0.2,
+                "JPY":147.678012345678901234
+            }
+        }"#;
+
+        let response: ExchangeRateResponse<Decimal> = serde_json::from_str(sample_json).unwrap();
+
+        // Rates are exact, with no binary floating point rounding
+        assert_eq!(
+            response.get_rate("JPY"),
+            Some(Decimal::from_str("147.678012345678901234").unwrap())
+        );
+        let eur = response.convert_from_base(Decimal::ONE, "EUR").unwrap();
+        let gbp = response.convert_from_base(Decimal::ONE, "GBP").unwrap();
+        assert_eq!(eur + gbp, Decimal::from_str("0.3").unwrap());
+
+        // Cross conversion through the base currency
+        assert_eq!(
+            response.convert(Decimal::from(10), "EUR", "GBP"),
+            Some(Decimal::from(20))
+        );
+
+        // Rebasing divides every rate by the new base's rate
+        let rebased = response.rebase("EUR").unwrap();
+        assert_eq!(rebased.base_code, "EUR");
+        assert_eq!(rebased.get_rate("USD"), Some(Decimal::from(10)));
+        assert_eq!(rebased.get_rate("GBP"), Some(Decimal::from(2)));
+        assert!(response.rebase("CHF").is_none());
+
+        // Round-trips through serialization as exact JSON numbers
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("147.678012345678901234"));
+
+        // And converts to the f64 API
+        let compat: ExchangeRateResponse = response.into();
+        assert_eq!(compat.get_rate("EUR"), Some(0.1));
     }
 }
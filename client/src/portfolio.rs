@@ -0,0 +1,242 @@
+//! Valuation of balances held in several currencies
+
+use crate::currency_code::{CurrencyCode, IntoCurrencyCode};
+use crate::{ExchangeRateClient, ExchangeRateError};
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Balances held in several currencies
+///
+/// Amounts are `f64` by default. With the `decimal` feature, a `Portfolio<Decimal>`
+/// is valued with exact decimal arithmetic instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{ExchangeRateClient, Portfolio};
+///
+/// # async fn example(client: ExchangeRateClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut portfolio = Portfolio::new();
+/// portfolio.add("USD".parse()?, 1_000.0);
+/// portfolio.add("EUR".parse()?, 250.0);
+/// portfolio.add("JPY".parse()?, 50_000.0);
+///
+/// let valuation = portfolio.value_in(&client, "GBP").await?;
+/// println!("Total: {:.2} GBP", valuation.total);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Portfolio<A = f64> {
+    positions: Vec<Position<A>>,
+}
+
+/// An amount held in one currency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position<A = f64> {
+    /// The currency the amount is held in
+    pub currency: CurrencyCode,
+
+    /// The amount held
+    pub amount: A,
+}
+
+/// The value of a portfolio in a reporting currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Valuation<A = f64> {
+    /// The reporting currency
+    pub target: CurrencyCode,
+
+    /// The total value of all positions in the reporting currency
+    pub total: A,
+
+    /// The value of each position, in the order they were added
+    pub positions: Vec<PositionValuation<A>>,
+
+    /// Whether the rates were served from an expired cache entry
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// The value of a single position in the reporting currency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PositionValuation<A = f64> {
+    /// The currency the amount is held in
+    pub currency: CurrencyCode,
+
+    /// The amount held
+    pub amount: A,
+
+    /// The rate used: units of the reporting currency per unit of `currency`
+    pub rate: A,
+
+    /// The value of the position in the reporting currency
+    pub value: A,
+
+    /// Unix time of the rates update the rate was taken from
+    pub time_last_update_unix: u64,
+}
+
+impl<A> Portfolio<A> {
+    /// Create an empty portfolio
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+        }
+    }
+
+    /// Add a position
+    pub fn add(&mut self, currency: CurrencyCode, amount: A) -> &mut Self {
+        self.positions.push(Position { currency, amount });
+        self
+    }
+
+    /// The positions, in the order they were added
+    #[must_use]
+    pub fn positions(&self) -> &[Position<A>] {
+        &self.positions
+    }
+
+    /// Check if the portfolio has no positions
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+impl Portfolio {
+    /// Value every position in a reporting currency
+    ///
+    /// All rates are taken from a single `latest` request for the reporting currency
+    /// (none while it is cached), however many currencies the portfolio holds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reporting currency is invalid, the API request fails,
+    /// or the API has no rate for one of the positions' currencies
+    pub async fn value_in(
+        &self,
+        client: &ExchangeRateClient,
+        target: impl IntoCurrencyCode,
+    ) -> Result<Valuation, ExchangeRateError> {
+        let target = target.into_currency_code()?;
+
+        // One response for the target base holds the rate for every position
+        let table = client.get_rate_table(target).await?;
+
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| {
+                let rate = table
+                    .rate(position.currency, target)
+                    .ok_or(ExchangeRateError::UnsupportedCode)?;
+
+                Ok(PositionValuation {
+                    currency: position.currency,
+                    amount: position.amount,
+                    rate,
+                    value: position.amount * rate,
+                    time_last_update_unix: table.time_last_update_unix(),
+                })
+            })
+            .collect::<Result<Vec<_>, ExchangeRateError>>()?;
+
+        Ok(Valuation {
+            target,
+            total: positions.iter().map(|position| position.value).sum(),
+            positions,
+            stale: table.is_stale(),
+        })
+    }
+}
+
+/// Exact decimal valuation
+#[cfg(feature = "decimal")]
+impl Portfolio<Decimal> {
+    /// Value every position in a reporting currency using exact decimal arithmetic
+    ///
+    /// All rates are taken from a single decimal `latest` request for the reporting
+    /// currency (none while it is cached), however many currencies the portfolio
+    /// holds. Each value is the amount divided by the reporting currency's rate for
+    /// the position's currency, so no precision is lost to an inverted rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reporting currency is invalid, the API request fails,
+    /// the API has no non-zero rate for one of the positions' currencies, or a value
+    /// or the total overflows
+    pub async fn value_in(
+        &self,
+        client: &ExchangeRateClient,
+        target: impl IntoCurrencyCode,
+    ) -> Result<Valuation<Decimal>, ExchangeRateError> {
+        let target = target.into_currency_code()?;
+
+        // Rates are units of each currency per unit of the reporting currency
+        let rates = client.get_latest_rates_decimal(target).await?;
+
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| {
+                let quote = if position.currency == target {
+                    Decimal::ONE
+                } else {
+                    rates
+                        .get_rate(position.currency)
+                        .filter(|quote| !quote.is_zero())
+                        .ok_or(ExchangeRateError::UnsupportedCode)?
+                };
+
+                Ok(PositionValuation {
+                    currency: position.currency,
+                    amount: position.amount,
+                    rate: Decimal::ONE
+                        .checked_div(quote)
+                        .ok_or(ExchangeRateError::Overflow)?,
+                    value: position
+                        .amount
+                        .checked_div(quote)
+                        .ok_or(ExchangeRateError::Overflow)?,
+                    time_last_update_unix: rates.time_last_update_unix,
+                })
+            })
+            .collect::<Result<Vec<_>, ExchangeRateError>>()?;
+
+        let total = positions
+            .iter()
+            .try_fold(Decimal::ZERO, |total, position| {
+                total.checked_add(position.value)
+            })
+            .ok_or(ExchangeRateError::Overflow)?;
+
+        Ok(Valuation {
+            target,
+            total,
+            positions,
+            stale: rates.stale,
+        })
+    }
+}
+
+impl<A> FromIterator<(CurrencyCode, A)> for Portfolio<A> {
+    fn from_iter<I: IntoIterator<Item = (CurrencyCode, A)>>(iter: I) -> Self {
+        Self {
+            positions: iter
+                .into_iter()
+                .map(|(currency, amount)| Position { currency, amount })
+                .collect(),
+        }
+    }
+}
+
+impl<A> Extend<(CurrencyCode, A)> for Portfolio<A> {
+    fn extend<I: IntoIterator<Item = (CurrencyCode, A)>>(&mut self, iter: I) {
+        for (currency, amount) in iter {
+            self.add(currency, amount);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Exchange rate providers and fallback between them
+
+use crate::{
+    CurrencyCode, ExchangeRateClient, ExchangeRateError, ExchangeRateResponse,
+    HistoricalRatesResponse, PairConversionResponse,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::future::Future;
+use std::sync::Arc;
+
+#[cfg(feature = "decimal")]
+use crate::Decimal;
+
+/// A source of exchange rates
+///
+/// `ExchangeRateClient` implements this for exchangerate-api.com, and can serve
+/// rates from any other provider set with
+/// [`ExchangeRateClientBuilder::provider`](crate::ExchangeRateClientBuilder::provider),
+/// applying its cache, network policy and stale handling on top. Providers should
+/// set [`ExchangeRateResponse::provider`] on the responses they return, which
+/// [`FallbackProvider`] does for them.
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    /// A short name identifying the provider, e.g. `exchangerate-api.com`
+    fn name(&self) -> &str;
+
+    /// Get the latest rates for a base currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates can't be fetched or the base isn't supported
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError>;
+
+    /// Get the latest rates for a base currency as exact decimals
+    ///
+    /// Converted from [`latest`](Self::latest) unless the provider has exact rates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates can't be fetched or the base isn't supported
+    #[cfg(feature = "decimal")]
+    async fn latest_decimal(
+        &self,
+        base: CurrencyCode,
+    ) -> Result<ExchangeRateResponse<Decimal>, ExchangeRateError> {
+        self.latest(base).await.map(Into::into)
+    }
+
+    /// Get the conversion rate between two currencies
+    ///
+    /// Derived from the latest rates for `from` unless the provider has a cheaper
+    /// way to get a single rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rate can't be fetched or either currency isn't supported
+    async fn pair(
+        &self,
+        from: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        let rates = self.latest(from).await?;
+        PairConversionResponse::from_latest(rates, to, None)
+            .ok_or(ExchangeRateError::UnsupportedCode)
+    }
+
+    /// Get the supported currency codes and their names
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the codes can't be fetched
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError>;
+
+    /// Get the rates for a base currency on a past date
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotSupported` unless the provider has historical rates, or an error
+    /// if the rates can't be fetched
+    async fn historical(
+        &self,
+        base: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let _ = (base, date);
+        Err(ExchangeRateError::NotSupported)
+    }
+}
+
+#[async_trait]
+impl RateProvider for ExchangeRateClient {
+    fn name(&self) -> &str {
+        self.provider.name()
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        let mut rates = self.get_latest_rates(base).await?;
+        rates
+            .provider
+            .get_or_insert_with(|| self.name().to_string());
+        Ok(rates)
+    }
+
+    async fn pair(
+        &self,
+        from: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        let mut pair = self.get_pair_conversion(from, to).await?;
+        pair.provider.get_or_insert_with(|| self.name().to_string());
+        Ok(pair)
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        self.get_supported_codes().await
+    }
+
+    async fn historical(
+        &self,
+        base: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let mut rates = self.get_historical_rates(base, date).await?;
+        rates
+            .provider
+            .get_or_insert_with(|| self.name().to_string());
+        Ok(rates)
+    }
+}
+
+/// A provider that tries a list of providers in order, returning the first answer
+///
+/// Each response's `provider` field records which provider answered. If every
+/// provider fails, the last error is returned.
+///
+/// To cache the answers, serve the fallback provider through an
+/// `ExchangeRateClient` with a cache backend.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ExchangeRateClient, FallbackProvider};
+/// use std::sync::Arc;
+///
+/// // Fall back to the keyless open access API if the paid one fails
+/// let primary = ExchangeRateClient::builder()
+///     .api_key("your-api-key")
+///     .disable_cache()
+///     .build()
+///     .unwrap();
+/// let backup = ExchangeRateClient::builder()
+///     .open_access()
+///     .disable_cache()
+///     .build()
+///     .unwrap();
+///
+/// let client = ExchangeRateClient::builder()
+///     .provider(Arc::new(FallbackProvider::new(vec![
+///         Arc::new(primary),
+///         Arc::new(backup),
+///     ])))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn RateProvider>>,
+}
+
+impl FallbackProvider {
+    /// Try `providers` in order
+    #[must_use]
+    pub fn new(providers: Vec<Arc<dyn RateProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// The providers, in the order they're tried
+    #[must_use]
+    pub fn providers(&self) -> &[Arc<dyn RateProvider>] {
+        &self.providers
+    }
+
+    /// Ask each provider in turn until one answers, returning it with its answer
+    async fn first_answer<'a, T, Fut>(
+        &'a self,
+        request: impl Fn(&'a dyn RateProvider) -> Fut,
+    ) -> Result<(&'a dyn RateProvider, T), ExchangeRateError>
+    where
+        Fut: Future<Output = Result<T, ExchangeRateError>>,
+    {
+        let mut last_error = ExchangeRateError::NotSupported;
+        for provider in &self.providers {
+            match request(provider.as_ref()).await {
+                Ok(answer) => return Ok((provider.as_ref(), answer)),
+                Err(err) => last_error = err,
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+#[async_trait]
+impl RateProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        let (provider, mut rates) = self.first_answer(|provider| provider.latest(base)).await?;
+        rates
+            .provider
+            .get_or_insert_with(|| provider.name().to_string());
+        Ok(rates)
+    }
+
+    async fn pair(
+        &self,
+        from: CurrencyCode,
+        to: CurrencyCode,
+    ) -> Result<PairConversionResponse, ExchangeRateError> {
+        let (provider, mut pair) = self
+            .first_answer(|provider| provider.pair(from, to))
+            .await?;
+        pair.provider
+            .get_or_insert_with(|| provider.name().to_string());
+        Ok(pair)
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        let (_, codes) = self.first_answer(|provider| provider.codes()).await?;
+        Ok(codes)
+    }
+
+    async fn historical(
+        &self,
+        base: CurrencyCode,
+        date: NaiveDate,
+    ) -> Result<HistoricalRatesResponse, ExchangeRateError> {
+        let (provider, mut rates) = self
+            .first_answer(|provider| provider.historical(base, date))
+            .await?;
+        rates
+            .provider
+            .get_or_insert_with(|| provider.name().to_string());
+        Ok(rates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FixedProvider, usd};
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn test_fallback_records_the_provider_that_answered() {
+        let down = FixedProvider::failing("down");
+        let backup = FixedProvider::quoting("backup", &[("EUR", 0.9)]);
+        let unused = FixedProvider::quoting("unused", &[("EUR", 0.8)]);
+        let fallback = FallbackProvider::new(vec![down.clone(), backup.clone(), unused.clone()]);
+
+        let rates = fallback.latest(usd()).await.unwrap();
+        assert_eq!(rates.get_rate("EUR"), Some(0.9));
+        assert_eq!(rates.provider.as_deref(), Some("backup"));
+        assert_eq!(down.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(unused.calls.load(Ordering::SeqCst), 0);
+
+        // Pairs default to the latest rates
+        let pair = fallback.pair(usd(), "EUR".parse().unwrap()).await.unwrap();
+        assert_eq!(pair.conversion_rate, 0.9);
+        assert_eq!(pair.provider.as_deref(), Some("backup"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_returns_the_last_error() {
+        let fallback = FallbackProvider::new(vec![
+            FixedProvider::failing("first"),
+            FixedProvider::failing("second"),
+        ]);
+
+        assert!(matches!(
+            fallback.latest(usd()).await,
+            Err(ExchangeRateError::HttpError(_))
+        ));
+
+        // Historical rates are optional, and these providers don't have them
+        let date = NaiveDate::from_ymd_opt(2025, 5, 14).unwrap();
+        assert!(matches!(
+            fallback.historical(usd(), date).await,
+            Err(ExchangeRateError::NotSupported)
+        ));
+        assert!(matches!(
+            FallbackProvider::new(Vec::new()).latest(usd()).await,
+            Err(ExchangeRateError::NotSupported)
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! Cross rates between any pair of currencies from a single base
+
+use crate::currency_code::{CurrencyCode, IntoCurrencyCode};
+use crate::models::ExchangeRateResponse;
+use std::collections::{BTreeMap, HashMap};
+
+/// A table of exchange rates from one base currency, answering any pair by
+/// triangulating through the base
+///
+/// One `latest` response holds enough data to convert between every pair of
+/// currencies it lists, so a `RateTable` saves a request per base currency.
+///
+/// # Examples
+///
+/// ```
+/// use client::{CurrencyCode, RateTable};
+/// use std::collections::HashMap;
+///
+/// let rates = HashMap::from([
+///     ("EUR".parse().unwrap(), 0.9),
+///     ("GBP".parse().unwrap(), 0.75),
+/// ]);
+/// let table = RateTable::new("USD".parse().unwrap(), rates);
+///
+/// // EUR -> GBP via USD
+/// let rate = table.rate("EUR", "GBP").unwrap();
+/// assert!((rate - 0.75 / 0.9).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateTable {
+    base: CurrencyCode,
+    rates: HashMap<CurrencyCode, f64>,
+    time_last_update_unix: u64,
+    time_next_update_unix: u64,
+    stale: bool,
+}
+
+impl RateTable {
+    /// Create a table from units of each currency per one unit of `base`
+    ///
+    /// The base currency's own rate is always 1. Non-positive rates are dropped,
+    /// since they can't be triangulated through.
+    #[must_use]
+    pub fn new(base: CurrencyCode, rates: HashMap<CurrencyCode, f64>) -> Self {
+        let mut rates: HashMap<_, _> = rates
+            .into_iter()
+            .filter(|(_, rate)| rate.is_finite() && *rate > 0.0)
+            .collect();
+        rates.insert(base, 1.0);
+
+        Self {
+            base,
+            rates,
+            time_last_update_unix: 0,
+            time_next_update_unix: 0,
+            stale: false,
+        }
+    }
+
+    /// The base currency
+    #[must_use]
+    pub const fn base(&self) -> CurrencyCode {
+        self.base
+    }
+
+    /// Unix time of the rates update the table was built from, or 0 if unknown
+    #[must_use]
+    pub const fn time_last_update_unix(&self) -> u64 {
+        self.time_last_update_unix
+    }
+
+    /// Unix time of the next rates update, or 0 if unknown
+    #[must_use]
+    pub const fn time_next_update_unix(&self) -> u64 {
+        self.time_next_update_unix
+    }
+
+    /// Check if the table was built from rates served from an expired cache entry
+    #[must_use]
+    pub const fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// The currencies in the table, in no particular order
+    pub fn currencies(&self) -> impl Iterator<Item = CurrencyCode> + '_ {
+        self.rates.keys().copied()
+    }
+
+    /// Check if the table has a rate for a currency
+    #[must_use]
+    pub fn contains(&self, currency: impl IntoCurrencyCode) -> bool {
+        currency
+            .into_currency_code()
+            .is_ok_and(|currency| self.rates.contains_key(&currency))
+    }
+
+    /// Get the rate from one currency to another: units of `to` per unit of `from`
+    #[must_use]
+    pub fn rate(&self, from: impl IntoCurrencyCode, to: impl IntoCurrencyCode) -> Option<f64> {
+        let from_rate = self.rates.get(&from.into_currency_code().ok()?)?;
+        let to_rate = self.rates.get(&to.into_currency_code().ok()?)?;
+
+        Some(to_rate / from_rate)
+    }
+
+    /// Convert an amount from one currency to another
+    #[must_use]
+    pub fn convert(
+        &self,
+        amount: f64,
+        from: impl IntoCurrencyCode,
+        to: impl IntoCurrencyCode,
+    ) -> Option<f64> {
+        self.rate(from, to).map(|rate| amount * rate)
+    }
+
+    /// Get the inverse of every rate: units of the base currency per unit of each currency
+    #[must_use]
+    pub fn invert(&self) -> HashMap<CurrencyCode, f64> {
+        self.rates
+            .iter()
+            .map(|(currency, rate)| (*currency, rate.recip()))
+            .collect()
+    }
+
+    /// Re-express the table relative to another currency in it
+    ///
+    /// Returns `None` if the table has no rate for `new_base`.
+    #[must_use]
+    pub fn rebase(&self, new_base: impl IntoCurrencyCode) -> Option<Self> {
+        let new_base = new_base.into_currency_code().ok()?;
+        let base_rate = *self.rates.get(&new_base)?;
+
+        let rates = self
+            .rates
+            .iter()
+            .map(|(currency, rate)| (*currency, rate / base_rate))
+            .collect();
+
+        Some(Self {
+            base: new_base,
+            rates,
+            ..*self
+        })
+    }
+
+    /// Build the conversion matrix between the given currencies
+    ///
+    /// `matrix[from][to]` is the rate from `from` to `to`. Currencies that are
+    /// invalid or not in the table are left out.
+    #[must_use]
+    pub fn matrix<C: IntoCurrencyCode>(
+        &self,
+        currencies: impl IntoIterator<Item = C>,
+    ) -> BTreeMap<CurrencyCode, BTreeMap<CurrencyCode, f64>> {
+        let currencies: Vec<(CurrencyCode, f64)> = currencies
+            .into_iter()
+            .filter_map(|currency| currency.into_currency_code().ok())
+            .filter_map(|currency| Some((currency, *self.rates.get(&currency)?)))
+            .collect();
+
+        currencies
+            .iter()
+            .map(|(from, from_rate)| {
+                let row = currencies
+                    .iter()
+                    .map(|(to, to_rate)| (*to, to_rate / from_rate))
+                    .collect();
+                (*from, row)
+            })
+            .collect()
+    }
+
+    /// Build the conversion matrix between every currency in the table
+    #[must_use]
+    pub fn full_matrix(&self) -> BTreeMap<CurrencyCode, BTreeMap<CurrencyCode, f64>> {
+        self.matrix(self.currencies())
+    }
+}
+
+impl From<&ExchangeRateResponse> for RateTable {
+    fn from(response: &ExchangeRateResponse) -> Self {
+        Self {
+            time_last_update_unix: response.time_last_update_unix,
+            time_next_update_unix: response.time_next_update_unix,
+            stale: response.stale,
+            ..Self::new(response.base_code, response.conversion_rates.clone())
+        }
+    }
+}
+
+impl From<ExchangeRateResponse> for RateTable {
+    fn from(response: ExchangeRateResponse) -> Self {
+        Self {
+            time_last_update_unix: response.time_last_update_unix,
+            time_next_update_unix: response.time_next_update_unix,
+            stale: response.stale,
+            ..Self::new(response.base_code, response.conversion_rates)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> RateTable {
+        let rates = HashMap::from([
+            ("EUR".parse().unwrap(), 0.8),
+            ("GBP".parse().unwrap(), 0.5),
+            ("JPY".parse().unwrap(), 100.0),
+        ]);
+        RateTable::new("USD".parse().unwrap(), rates)
+    }
+
+    #[test]
+    fn test_cross_rates() {
+        let table = table();
+
+        assert_eq!(table.rate("USD", "EUR"), Some(0.8));
+        assert_eq!(table.rate("EUR", "USD"), Some(1.25));
+        assert_eq!(table.rate("GBP", "JPY"), Some(200.0));
+        assert_eq!(table.rate("EUR", "EUR"), Some(1.0));
+        assert_eq!(table.convert(10.0, "GBP", "EUR"), Some(16.0));
+        assert_eq!(table.rate("USD", "CHF"), None);
+        assert_eq!(table.rate("USD", "EURO"), None);
+    }
+
+    #[test]
+    fn test_invert_and_rebase() {
+        let table = table();
+
+        let inverted = table.invert();
+        assert_eq!(inverted["EUR"], 1.25);
+        assert_eq!(inverted["USD"], 1.0);
+
+        let rebased = table.rebase("GBP").unwrap();
+        assert_eq!(rebased.base(), "GBP");
+        assert_eq!(rebased.rate("GBP", "USD"), Some(2.0));
+        assert_eq!(rebased.rate("GBP", "GBP"), Some(1.0));
+        assert_eq!(rebased.rate("EUR", "JPY"), table.rate("EUR", "JPY"));
+        assert!(table.rebase("CHF").is_none());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let table = table();
+
+        let matrix = table.matrix(["EUR", "GBP", "CHF"]);
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(matrix["EUR"]["GBP"], 0.625);
+        assert_eq!(matrix["GBP"]["EUR"], 1.6);
+        assert_eq!(matrix["GBP"]["GBP"], 1.0);
+
+        assert_eq!(table.full_matrix().len(), 4);
+    }
+}
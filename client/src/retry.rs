@@ -0,0 +1,255 @@
+//! Retrying failed requests with exponential backoff
+
+use crate::ExchangeRateError;
+use chrono::{DateTime, Utc};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// When and how often to retry a failed request
+///
+/// Delays grow exponentially from `base_delay` (1x, 2x, 4x, ...) up to `max_delay`.
+/// With jitter, each delay is drawn at random between half and all of that value,
+/// so that clients failing together don't retry together.
+///
+/// A `429 Too Many Requests` with a `Retry-After` header waits as long as the server
+/// asks instead. If that is longer than `max_delay`, the error is returned rather
+/// than waited out.
+///
+/// Authentication and account errors such as `InvalidKey` are never retried,
+/// whatever the `retry_if` predicate says.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ExchangeRateClient, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let client = ExchangeRateClient::builder()
+///     .api_key("your-api-key")
+///     .retry_policy(
+///         RetryPolicy::new(5)
+///             .base_delay(Duration::from_millis(200))
+///             .max_delay(Duration::from_secs(10)),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_if: fn(&ExchangeRateError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, starting at 500ms between them, with jitter
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy making up to `max_attempts` attempts in total
+    ///
+    /// Uses a 500ms base delay, a 30s maximum delay, jitter, and retries the
+    /// errors for which [`ExchangeRateError::is_retryable`] is true.
+    #[must_use]
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_if: ExchangeRateError::is_retryable,
+        }
+    }
+
+    /// A policy making a single attempt
+    #[must_use]
+    pub const fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Set the delay before the first retry
+    #[must_use]
+    pub const fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the longest delay between attempts
+    #[must_use]
+    pub const fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Enable or disable random jitter on the delays
+    #[must_use]
+    pub const fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Choose which errors are retried
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use client::{ExchangeRateError, RetryPolicy};
+    ///
+    /// // Also retry when the monthly quota is reached, e.g. just before it refreshes
+    /// let policy = RetryPolicy::default().retry_if(|error| {
+    ///     error.is_retryable() || matches!(error, ExchangeRateError::QuotaReached)
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn retry_if(mut self, predicate: fn(&ExchangeRateError) -> bool) -> Self {
+        self.retry_if = predicate;
+        self
+    }
+
+    /// The maximum number of attempts, including the first
+    #[must_use]
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Check if an error should be retried under this policy
+    #[must_use]
+    pub fn should_retry(&self, error: &ExchangeRateError) -> bool {
+        !error.is_auth_error() && (self.retry_if)(error)
+    }
+
+    /// The delay before retrying after the given (1-based) failed attempt, or
+    /// `None` if the request shouldn't be retried
+    pub(crate) fn delay_after(
+        &self,
+        attempt: u32,
+        error: &ExchangeRateError,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.should_retry(error) {
+            return None;
+        }
+
+        if let Some(retry_after) = retry_after {
+            return (retry_after <= self.max_delay).then_some(retry_after);
+        }
+
+        let exponential = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+
+        if self.jitter {
+            let half = exponential / 2;
+            Some(half + half.mul_f64(random_fraction()))
+        } else {
+            Some(exponential)
+        }
+    }
+}
+
+/// Parse a `Retry-After` header value, given either in seconds or as an HTTP date
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means retry now
+    Some(
+        date.with_timezone(&Utc)
+            .signed_duration_since(Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// A random number in `[0, 1)`, from the randomly seeded std hasher
+pub(crate) fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_exponential_delays() {
+        let policy = RetryPolicy::new(5)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(300))
+            .jitter(false);
+        let error = ExchangeRateError::HttpError(StatusCode::SERVICE_UNAVAILABLE);
+
+        assert_eq!(
+            policy.delay_after(1, &error, None),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy.delay_after(2, &error, None),
+            Some(Duration::from_millis(200))
+        );
+        // Capped at the maximum delay
+        assert_eq!(
+            policy.delay_after(3, &error, None),
+            Some(Duration::from_millis(300))
+        );
+        // No attempts left
+        assert_eq!(policy.delay_after(5, &error, None), None);
+    }
+
+    #[test]
+    fn test_jittered_delays_stay_in_range() {
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(100));
+        let error = ExchangeRateError::HttpError(StatusCode::BAD_GATEWAY);
+
+        for _ in 0..100 {
+            let delay = policy.delay_after(2, &error, None).unwrap();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let policy = RetryPolicy::new(3).max_delay(Duration::from_secs(60));
+        let error = ExchangeRateError::HttpError(StatusCode::TOO_MANY_REQUESTS);
+
+        assert_eq!(
+            policy.delay_after(1, &error, Some(Duration::from_secs(7))),
+            Some(Duration::from_secs(7))
+        );
+        // Longer than the policy allows, so not waited out
+        assert_eq!(
+            policy.delay_after(1, &error, Some(Duration::from_secs(120))),
+            None
+        );
+
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_auth_errors_never_retried() {
+        let policy = RetryPolicy::new(3).retry_if(|_| true);
+
+        assert!(!policy.should_retry(&ExchangeRateError::InvalidKey));
+        assert!(!policy.should_retry(&ExchangeRateError::InactiveAccount));
+        assert!(!policy.should_retry(&ExchangeRateError::MissingApiKey));
+        assert!(policy.should_retry(&ExchangeRateError::QuotaReached));
+        assert!(!RetryPolicy::default().should_retry(&ExchangeRateError::QuotaReached));
+    }
+}
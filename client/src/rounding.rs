@@ -0,0 +1,228 @@
+//! Rounding of converted amounts to a currency's minor unit
+
+use crate::currency_code::CurrencyCode;
+#[cfg(feature = "decimal")]
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// How to round an amount that falls between two representable values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Round to the nearest value, with halves going to the even neighbour
+    /// (banker's rounding)
+    #[default]
+    HalfEven,
+
+    /// Round to the nearest value, with halves going away from zero
+    HalfUp,
+
+    /// Round toward zero (truncate)
+    TowardZero,
+
+    /// Round away from zero
+    Up,
+}
+
+/// A policy for rounding amounts in a given currency
+///
+/// Amounts are rounded to the currency's minor unit from the [`currency`](crate::currency)
+/// registry, e.g. 2 decimal places for `EUR` and none for `JPY`. With cash rounding,
+/// amounts are instead rounded to the smallest coin in circulation where that is
+/// larger than the minor unit, e.g. 0.05 for `CHF` or whole units for `SEK`.
+///
+/// Amounts in currencies without a minor unit (such as `XAU`) are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use client::{CurrencyCode, RoundingMode, RoundingPolicy};
+///
+/// let chf: CurrencyCode = "CHF".parse().unwrap();
+///
+/// let policy = RoundingPolicy::new(RoundingMode::HalfEven);
+/// assert_eq!(policy.round(12.3456, chf), 12.35);
+/// assert_eq!(policy.with_cash_rounding().round(12.3456, chf), 12.35);
+/// assert_eq!(policy.with_cash_rounding().round(12.3234, chf), 12.3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RoundingPolicy {
+    mode: RoundingMode,
+    cash: bool,
+}
+
+impl RoundingPolicy {
+    /// Create a policy rounding to the currency's minor unit with the given mode
+    #[must_use]
+    pub const fn new(mode: RoundingMode) -> Self {
+        Self { mode, cash: false }
+    }
+
+    /// Round to the smallest cash denomination instead of the minor unit
+    #[must_use]
+    pub const fn with_cash_rounding(mut self) -> Self {
+        self.cash = true;
+        self
+    }
+
+    /// The rounding mode
+    #[must_use]
+    pub const fn mode(&self) -> RoundingMode {
+        self.mode
+    }
+
+    /// Check if cash rounding is enabled
+    #[must_use]
+    pub const fn is_cash_rounding(&self) -> bool {
+        self.cash
+    }
+
+    /// Round an amount in the given currency
+    #[must_use]
+    pub fn round(&self, amount: f64, currency: CurrencyCode) -> f64 {
+        let Some((step, minor_units)) = self.increment(currency) else {
+            return amount;
+        };
+
+        // Scale so that one increment is 1, snapping away binary representation
+        // error (e.g. 2.675 * 100 = 267.49999999999997) before rounding
+        let scale = 10_f64.powi(minor_units as i32) / f64::from(step);
+        let scaled = (amount * scale * 1e9).round() / 1e9;
+
+        let rounded = match self.mode {
+            RoundingMode::HalfEven => scaled.round_ties_even(),
+            RoundingMode::HalfUp => scaled.round(),
+            RoundingMode::TowardZero => scaled.trunc(),
+            RoundingMode::Up if scaled.is_sign_negative() => scaled.floor(),
+            RoundingMode::Up => scaled.ceil(),
+        };
+
+        rounded / scale
+    }
+
+    /// Round an exact decimal amount in the given currency
+    ///
+    /// Amounts too large to be divided into cash increments are returned unchanged.
+    #[cfg(feature = "decimal")]
+    #[must_use]
+    pub fn round_decimal(&self, amount: Decimal, currency: CurrencyCode) -> Decimal {
+        let Some((step, minor_units)) = self.increment(currency) else {
+            return amount;
+        };
+
+        let strategy = match self.mode {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::TowardZero => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+        };
+
+        if step == 1 {
+            return amount.round_dp_with_strategy(minor_units, strategy);
+        }
+
+        let increment = Decimal::new(i64::from(step), minor_units);
+        amount
+            .checked_div(increment)
+            .and_then(|steps| {
+                steps
+                    .round_dp_with_strategy(0, strategy)
+                    .checked_mul(increment)
+            })
+            .unwrap_or(amount)
+    }
+
+    /// The rounding increment for a currency, as a number of minor units and the
+    /// minor-unit exponent
+    fn increment(&self, currency: CurrencyCode) -> Option<(u32, u32)> {
+        let minor_units = u32::from(currency.currency()?.minor_units()?);
+        let step = if self.cash {
+            cash_increment(currency).unwrap_or(1)
+        } else {
+            1
+        };
+
+        Some((step, minor_units))
+    }
+}
+
+/// The smallest cash denomination of a currency in minor units, for currencies
+/// where it is larger than the minor unit
+///
+/// For example `CHF` has no coins below 5 centimes, so this returns `Some(5)`.
+#[must_use]
+pub fn cash_increment(currency: CurrencyCode) -> Option<u32> {
+    match currency.as_str() {
+        "AUD" | "CAD" | "CHF" => Some(5),
+        "NZD" => Some(10),
+        "DKK" => Some(50),
+        "CZK" | "NOK" | "SEK" => Some(100),
+        "HUF" => Some(500),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(code: &str) -> CurrencyCode {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn test_round_to_minor_units() {
+        let half_even = RoundingPolicy::new(RoundingMode::HalfEven);
+        assert_eq!(half_even.round(2.675, code("EUR")), 2.68);
+        assert_eq!(half_even.round(2.665, code("EUR")), 2.66);
+        assert_eq!(half_even.round(1234.5, code("JPY")), 1234.0);
+        assert_eq!(half_even.round(1.23456, code("KWD")), 1.235);
+        // No minor unit, so left unchanged
+        assert_eq!(half_even.round(0.123456, code("XAU")), 0.123456);
+
+        let half_up = RoundingPolicy::new(RoundingMode::HalfUp);
+        assert_eq!(half_up.round(2.665, code("EUR")), 2.67);
+        assert_eq!(half_up.round(-2.665, code("EUR")), -2.67);
+
+        let toward_zero = RoundingPolicy::new(RoundingMode::TowardZero);
+        assert_eq!(toward_zero.round(2.679, code("EUR")), 2.67);
+        assert_eq!(toward_zero.round(-2.679, code("EUR")), -2.67);
+
+        let up = RoundingPolicy::new(RoundingMode::Up);
+        assert_eq!(up.round(2.671, code("EUR")), 2.68);
+        assert_eq!(up.round(-2.671, code("EUR")), -2.68);
+        assert_eq!(up.round(2.67, code("EUR")), 2.67);
+    }
+
+    #[test]
+    fn test_cash_rounding() {
+        let cash = RoundingPolicy::new(RoundingMode::HalfEven).with_cash_rounding();
+        assert_eq!(cash.round(12.37, code("CHF")), 12.35);
+        assert_eq!(cash.round(12.38, code("CHF")), 12.4);
+        assert_eq!(cash.round(99.49, code("SEK")), 99.0);
+        assert_eq!(cash.round(99.5, code("SEK")), 100.0);
+        // Currencies without a cash increment round to the minor unit
+        assert_eq!(cash.round(12.345, code("EUR")), 12.34);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_round_decimal() {
+        use std::str::FromStr;
+
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        let half_even = RoundingPolicy::new(RoundingMode::HalfEven);
+        assert_eq!(
+            half_even.round_decimal(dec("2.665"), code("EUR")),
+            dec("2.66")
+        );
+        assert_eq!(
+            half_even.round_decimal(dec("1234.5"), code("JPY")),
+            dec("1234")
+        );
+
+        let up = RoundingPolicy::new(RoundingMode::Up).with_cash_rounding();
+        assert_eq!(up.round_decimal(dec("12.31"), code("CHF")), dec("12.35"));
+        assert_eq!(up.round_decimal(dec("-12.31"), code("CHF")), dec("-12.35"));
+        assert_eq!(up.round_decimal(dec("98.01"), code("SEK")), dec("99"));
+    }
+}
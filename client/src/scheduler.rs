@@ -0,0 +1,203 @@
+//! Refreshing cached rates in the background as the API publishes them
+
+use crate::retry::random_fraction;
+use crate::{CurrencyCode, ExchangeRateClient, ExchangeRateError};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Called with the base currency and the error when a refresh fails
+type ErrorCallback = Arc<dyn Fn(CurrencyCode, &ExchangeRateError) + Send + Sync>;
+
+/// Keeps the latest rates for a set of base currencies fresh in the client's cache
+///
+/// Each response's `time_next_update_unix` says when the API will publish new
+/// rates. The scheduler fetches each base again shortly after that time, plus a
+/// random jitter so that many services don't call the API at once, so requests
+/// for latest rates and conversions are served from the cache instead of waiting
+/// on the API.
+///
+/// Every base is fetched once when the scheduler starts. Failed refreshes are
+/// reported to the [`on_error`](Self::on_error) callback and tried again after the
+/// retry interval, as are responses whose next update is already due.
+///
+/// Only the `f64` rates of `get_latest_rates` are refreshed, not the decimal ones.
+/// In single-base mode only the single base is refreshed, as every other base is
+/// derived from its rates.
+///
+/// # Examples
+///
+/// ```no_run
+/// use client::{ExchangeRateClient, RefreshScheduler};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = ExchangeRateClient::builder()
+///         .api_key("your-api-key")
+///         .build()?;
+///
+///     let refresh = RefreshScheduler::new(client.clone(), ["USD".parse()?, "EUR".parse()?])
+///         .on_error(|base, error| eprintln!("Failed to refresh {} rates: {}", base, error))
+///         .spawn();
+///
+///     // Serve requests with `client`...
+///
+///     refresh.shutdown().await;
+///     Ok(())
+/// }
+/// ```
+pub struct RefreshScheduler {
+    client: ExchangeRateClient,
+    bases: Vec<CurrencyCode>,
+    delay: Duration,
+    jitter: Duration,
+    retry_interval: Duration,
+    on_error: Option<ErrorCallback>,
+}
+
+impl RefreshScheduler {
+    /// Create a scheduler refreshing the latest rates for `bases`
+    ///
+    /// Refreshes happen a minute after each publish time, plus up to a minute of
+    /// jitter, and failed refreshes are retried every five minutes.
+    #[must_use]
+    pub fn new(client: ExchangeRateClient, bases: impl IntoIterator<Item = CurrencyCode>) -> Self {
+        // Refresh what the client reads: with a single base, that's its rates alone
+        let mut unique = Vec::new();
+        for base in bases {
+            let base = client.single_base.unwrap_or(base);
+            if !unique.contains(&base) {
+                unique.push(base);
+            }
+        }
+
+        Self {
+            client,
+            bases: unique,
+            delay: Duration::from_secs(60),
+            jitter: Duration::from_secs(60),
+            retry_interval: Duration::from_secs(300),
+            on_error: None,
+        }
+    }
+
+    /// Set how long after each publish time to refresh
+    #[must_use]
+    pub const fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Set the longest random delay added to each refresh
+    #[must_use]
+    pub const fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set how long to wait before trying a failed refresh again
+    #[must_use]
+    pub const fn retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Report failed refreshes to a callback, e.g. to log them or count them in metrics
+    #[must_use]
+    pub fn on_error(
+        mut self,
+        callback: impl Fn(CurrencyCode, &ExchangeRateError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Start refreshing in a background task
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime
+    #[must_use = "dropping the handle stops the scheduler"]
+    pub fn spawn(self) -> RefreshHandle {
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let task = tokio::spawn(self.run(shutdown_rx));
+
+        RefreshHandle { shutdown, task }
+    }
+
+    /// Refresh each base when it's due until shut down
+    async fn run(self, mut shutdown: oneshot::Receiver<()>) {
+        let started = Instant::now();
+        let mut due: Vec<(CurrencyCode, Instant)> =
+            self.bases.iter().map(|&base| (base, started)).collect();
+
+        while let Some(index) = (0..due.len()).min_by_key(|&index| due[index].1) {
+            let (base, at) = due[index];
+            let refresh = async {
+                tokio::time::sleep_until(at).await;
+                self.refresh(base).await
+            };
+
+            // Also stops when the handle is dropped
+            tokio::select! {
+                _ = &mut shutdown => break,
+                next = refresh => due[index].1 = next,
+            }
+        }
+    }
+
+    /// Refresh a base, returning when it's next due
+    async fn refresh(&self, base: CurrencyCode) -> Instant {
+        match self.client.refresh_latest_rates(base).await {
+            Ok(rates) => match until(rates.time_next_update_unix) {
+                Some(publish_in) => Instant::now() + publish_in + self.delay + self.random_jitter(),
+                // The next update is already due, so it's probably running late
+                None => Instant::now() + self.retry_interval + self.random_jitter(),
+            },
+            Err(err) => {
+                if let Some(on_error) = &self.on_error {
+                    on_error(base, &err);
+                }
+                Instant::now() + self.retry_interval + self.random_jitter()
+            }
+        }
+    }
+
+    fn random_jitter(&self) -> Duration {
+        self.jitter.mul_f64(random_fraction())
+    }
+}
+
+/// How long until a Unix timestamp, or `None` if it has passed
+fn until(timestamp: u64) -> Option<Duration> {
+    let time = DateTime::from_timestamp(i64::try_from(timestamp).ok()?, 0)?;
+    time.signed_duration_since(Utc::now()).to_std().ok()
+}
+
+/// A handle to a running [`RefreshScheduler`]
+///
+/// Dropping the handle stops the scheduler as well.
+pub struct RefreshHandle {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl RefreshHandle {
+    /// Stop the scheduler and wait for its task to finish
+    ///
+    /// A refresh in progress is abandoned.
+    pub async fn shutdown(self) {
+        // An error means the task has already stopped
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+
+    /// Check if the scheduler has stopped
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
@@ -0,0 +1,106 @@
+//! Coalescing of concurrent identical requests
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// Runs at most one operation per key at a time, sharing its result with every
+/// caller that asked for the same key while it was running
+///
+/// Once an operation finishes, the next caller for its key starts a new one: results
+/// are shared between concurrent callers, not cached. If the caller running an
+/// operation is cancelled, one of the waiting callers runs it instead.
+pub(crate) struct SingleFlight<T> {
+    in_flight: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    /// Run `operation` for `key`, or wait for the run already in flight
+    pub(crate) async fn run<F, Fut>(&self, key: &str, operation: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+
+        let result = flight.get_or_init(operation).await.clone();
+
+        // Let the next caller start a fresh run, unless one already has
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if in_flight
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, &flight))
+        {
+            in_flight.remove(key);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_run() {
+        let flights = Arc::new(SingleFlight::<Result<u32, String>>::default());
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..20)
+            .map(|_| {
+                let flights = flights.clone();
+                let runs = runs.clone();
+                tokio::spawn(async move {
+                    flights
+                        .run("latest:USD", || async {
+                            runs.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Err("unavailable".to_string())
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), Err("unavailable".to_string()));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Finished runs aren't reused
+        let result = flights.run("latest:USD", || async { Ok(1) }).await;
+        assert_eq!(result, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn test_different_keys_run_separately() {
+        let flights = SingleFlight::<u32>::default();
+
+        let (usd, eur) = tokio::join!(
+            flights.run("latest:USD", || async { 1 }),
+            flights.run("latest:EUR", || async { 2 }),
+        );
+        assert_eq!((usd, eur), (1, 2));
+    }
+}
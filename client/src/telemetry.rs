@@ -0,0 +1,160 @@
+//! Tracing spans and events for requests, emitted with the `tracing` feature
+//!
+//! Without the feature every function here does nothing, so the client never
+//! writes diagnostics of its own.
+
+use crate::ExchangeRateError;
+use std::fmt;
+use std::future::Future;
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// Replaces the API key in diagnostics
+#[cfg(feature = "tracing")]
+const REDACTED: &str = "[REDACTED]";
+
+/// How a request was answered with respect to the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CacheStatus {
+    /// A fresh cache entry was returned
+    Hit,
+
+    /// There was no fresh entry, so the rates were fetched
+    Miss,
+
+    /// An expired entry was returned
+    Stale,
+
+    /// The cache wasn't consulted, because it's disabled or the request is never cached
+    Bypass,
+}
+
+impl CacheStatus {
+    #[cfg(feature = "tracing")]
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Miss => "miss",
+            Self::Stale => "stale",
+            Self::Bypass => "bypass",
+        }
+    }
+}
+
+/// The `exchange_rate_request` span covering one client request
+///
+/// Records the endpoint, base currency and provider when created, and the cache
+/// status, latency and outcome as the request completes.
+pub(crate) struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    started: Instant,
+}
+
+impl RequestSpan {
+    /// Open a span for a request to `endpoint`, whose first parameter is the base currency
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn new(endpoint: &str, params: &[&str], provider: &str) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "exchange_rate_request",
+                endpoint,
+                base = params.first().copied(),
+                provider,
+                cache = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                status = tracing::field::Empty,
+                error = tracing::field::Empty,
+            ),
+            #[cfg(feature = "tracing")]
+            started: Instant::now(),
+        }
+    }
+
+    /// Record how the request was answered with respect to the cache
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn cache(&self, status: CacheStatus) {
+        #[cfg(feature = "tracing")]
+        self.span.record("cache", status.as_str());
+    }
+
+    /// Run the request inside the span, then record its latency and outcome
+    ///
+    /// Error messages are recorded with `api_key` redacted, as some include the URL.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) async fn track<T>(
+        &self,
+        api_key: &str,
+        request: impl Future<Output = Result<T, ExchangeRateError>>,
+    ) -> Result<T, ExchangeRateError> {
+        #[cfg(feature = "tracing")]
+        let result = {
+            use tracing::Instrument;
+            request.instrument(self.span.clone()).await
+        };
+        #[cfg(not(feature = "tracing"))]
+        let result = request.await;
+
+        #[cfg(feature = "tracing")]
+        {
+            let latency_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            self.span.record("latency_ms", latency_ms);
+
+            match &result {
+                Ok(_) => {
+                    self.span.record("status", "ok");
+                }
+                Err(err) => {
+                    let error = redact(&err.to_string(), api_key);
+                    self.span.record("status", "error");
+                    self.span.record("error", error.as_str());
+                }
+            }
+
+            self.span.in_scope(|| {
+                tracing::debug!(latency_ms, success = result.is_ok(), "request completed");
+            });
+        }
+
+        result
+    }
+}
+
+/// Record the outcome of one HTTP attempt, with the API key redacted from the URL
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn http_attempt(
+    url: &str,
+    api_key: &str,
+    outcome: Result<reqwest::StatusCode, &ExchangeRateError>,
+) {
+    #[cfg(feature = "tracing")]
+    {
+        let url = redact(url, api_key);
+        match outcome {
+            Ok(status) => tracing::debug!(url, http_status = status.as_u16(), "HTTP response"),
+            Err(err) => {
+                let error = redact(&err.to_string(), api_key);
+                tracing::debug!(url, error, "HTTP request failed");
+            }
+        }
+    }
+}
+
+/// Report a failure the client recovered from, such as a cache write that failed
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn recovered(message: &'static str, error: &dyn fmt::Display) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(error = %error, "{}", message);
+}
+
+/// Replace every occurrence of the API key
+#[cfg(feature = "tracing")]
+fn redact(text: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(api_key, REDACTED)
+    }
+}
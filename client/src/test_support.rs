@@ -0,0 +1,112 @@
+//! Helpers shared by the unit tests of several modules
+
+use crate::{
+    AuthMethod, CurrencyCode, ExchangeRateClient, ExchangeRateClientBuilder, ExchangeRateError,
+    ExchangeRateResponse, FakeTransport, RateProvider,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A latest rates response for USD that stays fresh until 2100
+pub(crate) const LATEST_USD: &str = r#"{
+    "result":"success",
+    "documentation":"https://www.exchangerate-api.com/docs",
+    "terms_of_use":"https://www.exchangerate-api.com/terms",
+    "time_last_update_unix":1747180802,
+    "time_last_update_utc":"Wed, 14 May 2025 00:00:02 +0000",
+    "time_next_update_unix":4102444800,
+    "time_next_update_utc":"Fri, 01 Jan 2100 00:00:00 +0000",
+    "base_code":"USD",
+    "conversion_rates":{"USD":1,"EUR":0.8}
+}"#;
+
+/// A builder for a client sending requests through a fake transport
+pub(crate) fn fake_builder(
+    transport: &Arc<FakeTransport>,
+    auth_method: AuthMethod,
+) -> ExchangeRateClientBuilder {
+    ExchangeRateClient::builder()
+        .api_key("test_key")
+        .auth_method(auth_method)
+        .transport(transport.clone())
+}
+
+/// A client sending requests through a fake transport
+pub(crate) fn fake_client(
+    transport: &Arc<FakeTransport>,
+    auth_method: AuthMethod,
+) -> ExchangeRateClient {
+    fake_builder(transport, auth_method).build().unwrap()
+}
+
+/// A provider with fixed latest rates, or failing without any
+pub(crate) struct FixedProvider {
+    name: &'static str,
+    rates: Option<Vec<(&'static str, f64)>>,
+    /// How many times the latest rates were asked for
+    pub(crate) calls: AtomicUsize,
+}
+
+impl FixedProvider {
+    /// A provider quoting `rates` against whichever base is asked for
+    pub(crate) fn quoting(name: &'static str, rates: &[(&'static str, f64)]) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            rates: Some(rates.to_vec()),
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    /// A provider failing with a `502 Bad Gateway`
+    pub(crate) fn failing(name: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            rates: None,
+            calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl RateProvider for FixedProvider {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn latest(&self, base: CurrencyCode) -> Result<ExchangeRateResponse, ExchangeRateError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let rates = self.rates.as_ref().ok_or(ExchangeRateError::HttpError(
+            reqwest::StatusCode::BAD_GATEWAY,
+        ))?;
+        let mut conversion_rates: HashMap<_, _> = rates
+            .iter()
+            .map(|(code, rate)| (code.parse().unwrap(), *rate))
+            .collect();
+        conversion_rates.insert(base, 1.0);
+
+        Ok(ExchangeRateResponse {
+            result: "success".to_string(),
+            documentation: String::new(),
+            terms_of_use: String::new(),
+            time_last_update_unix: 1747180802,
+            time_last_update_utc: "Wed, 14 May 2025 00:00:02 +0000".to_string(),
+            time_next_update_unix: 4102444800,
+            time_next_update_utc: "Fri, 01 Jan 2100 00:00:00 +0000".to_string(),
+            base_code: base,
+            conversion_rates,
+            provider: None,
+            consensus: None,
+            stale: false,
+        })
+    }
+
+    async fn codes(&self) -> Result<Vec<(CurrencyCode, String)>, ExchangeRateError> {
+        Ok(vec![(usd(), "US Dollar".to_string())])
+    }
+}
+
+pub(crate) fn usd() -> CurrencyCode {
+    "USD".parse().unwrap()
+}
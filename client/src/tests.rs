@@ -1,21 +1,23 @@
+use crate::test_support::{LATEST_USD, fake_client};
 #[cfg(test)]
-use crate::{AuthMethod, CacheBackend, CacheConfig, ExchangeRateClient, InMemoryCache};
+use crate::{
+    AuthMethod, CacheBackend, CurrencyCodeError, ExchangeRateClient, ExchangeRateError,
+    FakeTransport, HttpResponse, InMemoryCache, NetworkPolicy, RetryPolicy, check_api_response,
+};
+use reqwest::StatusCode;
 use std::env;
 use std::sync::Arc;
 use std::time::Duration;
 
 #[test]
 fn test_url_construction_in_url_auth() {
-    let client = ExchangeRateClient {
-        api_key: "test_key".to_string(),
-        base_url: "https://v6.exchangerate-api.com/v6".to_string(),
-        auth_method: AuthMethod::InUrl,
-        http_client: reqwest::Client::new(),
-        cache: None,
-        cache_config: CacheConfig::default(),
-    };
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .auth_method(AuthMethod::InUrl)
+        .build()
+        .unwrap();
 
-    let url = client.build_url("latest", &["USD"]);
+    let url = client.api.build_url("latest", &["USD"]);
     assert_eq!(
         url,
         "https://v6.exchangerate-api.com/v6/test_key/latest/USD"
@@ -24,19 +26,149 @@ fn test_url_construction_in_url_auth() {
 
 #[test]
 fn test_url_construction_bearer_token() {
-    let client = ExchangeRateClient {
-        api_key: "test_key".to_string(),
-        base_url: "https://v6.exchangerate-api.com/v6".to_string(),
-        auth_method: AuthMethod::BearerToken,
-        http_client: reqwest::Client::new(),
-        cache: None,
-        cache_config: CacheConfig::default(),
-    };
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .auth_method(AuthMethod::BearerToken)
+        .build()
+        .unwrap();
 
-    let url = client.build_url("latest", &["USD"]);
+    let url = client.api.build_url("latest", &["USD"]);
     assert_eq!(url, "https://v6.exchangerate-api.com/v6/latest/USD");
 }
 
+#[test]
+fn test_url_construction_without_params() {
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .auth_method(AuthMethod::InUrl)
+        .build()
+        .unwrap();
+
+    let url = client.api.build_url("quota", &[]);
+    assert_eq!(url, "https://v6.exchangerate-api.com/v6/test_key/quota");
+}
+
+#[test]
+fn test_api_error_envelope_decoding() {
+    let cases = [
+        ("invalid-key", StatusCode::FORBIDDEN),
+        ("inactive-account", StatusCode::FORBIDDEN),
+        ("quota-reached", StatusCode::TOO_MANY_REQUESTS),
+        ("unsupported-code", StatusCode::NOT_FOUND),
+        ("malformed-request", StatusCode::BAD_REQUEST),
+        ("plan-upgrade-required", StatusCode::FORBIDDEN),
+        ("no-data-available", StatusCode::NOT_FOUND),
+    ];
+
+    for (error_type, status) in cases {
+        let body = format!(r#"{{"result":"error","error-type":"{error_type}"}}"#);
+
+        // The error type wins over the HTTP status, and is decoded on a 200 too
+        for status in [status, StatusCode::OK] {
+            let err = check_api_response(status, &body).unwrap_err();
+            let matched = match error_type {
+                "invalid-key" => matches!(err, ExchangeRateError::InvalidKey),
+                "inactive-account" => matches!(err, ExchangeRateError::InactiveAccount),
+                "quota-reached" => matches!(err, ExchangeRateError::QuotaReached),
+                "unsupported-code" => matches!(err, ExchangeRateError::UnsupportedCode),
+                "malformed-request" => matches!(err, ExchangeRateError::MalformedRequest),
+                "plan-upgrade-required" => matches!(err, ExchangeRateError::PlanUpgradeRequired),
+                "no-data-available" => matches!(err, ExchangeRateError::NoDataAvailable),
+                _ => unreachable!(),
+            };
+            assert!(matched, "{error_type} decoded as {err:?}");
+        }
+    }
+}
+
+#[test]
+fn test_api_error_unknown_and_http_errors() {
+    // Unknown error types keep their raw string
+    let err = check_api_response(
+        StatusCode::OK,
+        r#"{"result":"error","error-type":"brand-new-error"}"#,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ExchangeRateError::UnknownApiError(ref t) if t == "brand-new-error"));
+
+    // Non-JSON error bodies fall back to the HTTP status
+    let err = check_api_response(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>").unwrap_err();
+    assert!(matches!(
+        err,
+        ExchangeRateError::HttpError(StatusCode::BAD_GATEWAY)
+    ));
+
+    // Successful responses pass through
+    assert!(check_api_response(StatusCode::OK, r#"{"result":"success"}"#).is_ok());
+}
+
+#[test]
+fn test_open_access_builder() {
+    // No API key is needed for open access
+    let client = ExchangeRateClient::builder().open_access().build().unwrap();
+
+    assert!(client.is_open_access());
+    assert_eq!(
+        client.api.build_url("latest", &["USD"]),
+        "https://open.er-api.com/v6/latest/USD"
+    );
+
+    // Other authentication methods still require a key
+    assert!(matches!(
+        ExchangeRateClient::builder().build(),
+        Err(ExchangeRateError::MissingApiKey)
+    ));
+}
+
+#[tokio::test]
+async fn test_open_access_rejects_keyed_endpoints() {
+    let client = ExchangeRateClient::builder()
+        .auth_method(AuthMethod::None)
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        client.get_quota().await,
+        Err(ExchangeRateError::MissingApiKey)
+    ));
+    assert!(matches!(
+        client.get_supported_codes().await,
+        Err(ExchangeRateError::MissingApiKey)
+    ));
+}
+
+#[tokio::test]
+async fn test_invalid_currency_codes_rejected_before_request() {
+    // The transport has no responses, so these only pass if validation happens first
+    let transport = Arc::new(FakeTransport::new());
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .disable_cache()
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        client.get_latest_rates("EURO").await,
+        Err(ExchangeRateError::InvalidCurrencyCode(
+            CurrencyCodeError::InvalidLength(_)
+        ))
+    ));
+    assert!(matches!(
+        client.convert(1.0, "USD", "XYZ").await,
+        Err(ExchangeRateError::InvalidCurrencyCode(
+            CurrencyCodeError::Unknown(_)
+        ))
+    ));
+    assert!(matches!(
+        client.get_pair_conversion("U$D", "EUR").await,
+        Err(ExchangeRateError::InvalidCurrencyCode(
+            CurrencyCodeError::InvalidCharacters(_)
+        ))
+    ));
+    assert_eq!(transport.request_count(), 0);
+}
+
 #[test]
 fn test_builder_pattern() {
     let client = ExchangeRateClient::builder()
@@ -46,8 +178,8 @@ fn test_builder_pattern() {
         .build()
         .unwrap();
 
-    assert_eq!(client.api_key, "test_key");
-    assert!(matches!(client.auth_method, AuthMethod::BearerToken));
+    assert_eq!(client.api.api_key, "test_key");
+    assert!(matches!(client.api.auth_method, AuthMethod::BearerToken));
 }
 
 #[tokio::test]
@@ -128,8 +260,8 @@ async fn test_caching() {
     use std::collections::HashMap;
 
     let mut rates = HashMap::new();
-    rates.insert("EUR".to_string(), 0.85);
-    rates.insert("GBP".to_string(), 0.75);
+    rates.insert("EUR".parse().unwrap(), 0.85);
+    rates.insert("GBP".parse().unwrap(), 0.75);
 
     let response = ExchangeRateResponse {
         result: "success".to_string(),
@@ -139,8 +271,11 @@ async fn test_caching() {
         time_last_update_utc: "Mon, 03 May 2021 00:00:00 +0000".to_string(),
         time_next_update_unix: 1620086400,
         time_next_update_utc: "Tue, 04 May 2021 00:00:00 +0000".to_string(),
-        base_code: "USD".to_string(),
+        base_code: "USD".parse().unwrap(),
         conversion_rates: rates,
+        provider: None,
+        consensus: None,
+        stale: false,
     };
 
     // Store the response in the cache
@@ -178,3 +313,1063 @@ async fn test_caching() {
     assert_eq!(retrieved.get_rate("EUR").unwrap(), 0.85);
     assert_eq!(retrieved.get_rate("GBP").unwrap(), 0.75);
 }
+
+/// Cache latest rates for `base`, published on 14 May 2025 and expiring at `expires_at`
+#[cfg(test)]
+async fn cache_latest(
+    cache: &InMemoryCache,
+    base: &str,
+    rates: &[(&str, f64)],
+    expires_at: chrono::DateTime<chrono::Utc>,
+) {
+    use crate::cache::{CachedResponse, create_cache_key};
+    use crate::models::ExchangeRateResponse;
+
+    let response = ExchangeRateResponse {
+        result: "success".to_string(),
+        documentation: "https://www.exchangerate-api.com/docs".to_string(),
+        terms_of_use: "https://www.exchangerate-api.com/terms".to_string(),
+        time_last_update_unix: 1747180802,
+        time_last_update_utc: "Wed, 14 May 2025 00:00:02 +0000".to_string(),
+        time_next_update_unix: 1747267202,
+        time_next_update_utc: "Thu, 15 May 2025 00:00:02 +0000".to_string(),
+        base_code: base.parse().unwrap(),
+        conversion_rates: rates
+            .iter()
+            .map(|&(code, rate)| (code.parse().unwrap(), rate))
+            .collect(),
+        provider: None,
+        consensus: None,
+        stale: false,
+    };
+
+    let cached_response = CachedResponse {
+        response,
+        cached_at: expires_at - chrono::Duration::days(1),
+        expires_at,
+    };
+    cache
+        .set_exchange_rate(&create_cache_key("latest", &[base]), cached_response)
+        .await
+        .unwrap();
+}
+
+/// An hour from now, for cache entries that are still fresh
+#[cfg(test)]
+fn in_an_hour() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() + chrono::Duration::hours(1)
+}
+
+/// An hour ago, for cache entries that have expired
+#[cfg(test)]
+fn an_hour_ago() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() - chrono::Duration::hours(1)
+}
+
+#[tokio::test]
+async fn test_convert_applies_rounding_policy() {
+    use crate::{RoundingMode, RoundingPolicy};
+
+    let cache = Arc::new(InMemoryCache::new());
+    cache_latest(
+        &cache,
+        "USD",
+        &[("USD", 1.0), ("CHF", 0.8377), ("JPY", 147.678)],
+        in_an_hour(),
+    )
+    .await;
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .with_cache(cache.clone())
+        .rounding(RoundingPolicy::new(RoundingMode::HalfEven).with_cash_rounding())
+        .build()
+        .unwrap();
+
+    // 12.34 * 0.8377 = 10.337218, rounded to the nearest 5 centimes
+    assert_eq!(client.convert(12.34, "USD", "CHF").await.unwrap(), 10.35);
+    // 12.34 * 147.678 = 1822.34652, rounded to whole yen
+    assert_eq!(client.convert(12.34, "USD", "JPY").await.unwrap(), 1822.0);
+}
+
+#[tokio::test]
+async fn test_single_base_mode_serves_other_bases_from_cache() {
+    let cache = Arc::new(InMemoryCache::new());
+    cache_latest(
+        &cache,
+        "USD",
+        &[("USD", 1.0), ("EUR", 0.8), ("GBP", 0.5)],
+        in_an_hour(),
+    )
+    .await;
+
+    // Only the USD rates are cached and the transport has no responses, so every
+    // other base has to be derived from them
+    let transport = Arc::new(FakeTransport::new());
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache.clone())
+        .single_base("USD".parse().unwrap())
+        .build()
+        .unwrap();
+
+    let eur = client.get_latest_rates("EUR").await.unwrap();
+    assert_eq!(eur.base_code, "EUR");
+    assert_eq!(eur.get_rate("USD"), Some(1.25));
+    assert_eq!(eur.get_rate("GBP"), Some(0.625));
+
+    assert_eq!(client.convert(10.0, "GBP", "EUR").await.unwrap(), 16.0);
+
+    let pair = client.get_pair_conversion("GBP", "USD").await.unwrap();
+    assert_eq!(pair.base_code, "GBP");
+    assert_eq!(pair.conversion_rate, 2.0);
+
+    let table = client.get_rate_table("USD").await.unwrap();
+    assert_eq!(table.rate("EUR", "GBP"), Some(0.625));
+    assert_eq!(table.time_last_update_unix(), 1747180802);
+    assert_eq!(transport.request_count(), 0);
+}
+
+#[tokio::test]
+async fn test_portfolio_valued_from_one_response() {
+    use crate::Portfolio;
+
+    let cache = Arc::new(InMemoryCache::new());
+    cache_latest(
+        &cache,
+        "GBP",
+        &[("GBP", 1.0), ("USD", 2.0), ("EUR", 1.25)],
+        in_an_hour(),
+    )
+    .await;
+
+    // Only the GBP rates are cached and the transport has no responses, so any
+    // request beyond the one for the reporting currency would fail
+    let transport = Arc::new(FakeTransport::new());
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache.clone())
+        .build()
+        .unwrap();
+
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .add("USD".parse().unwrap(), 100.0)
+        .add("EUR".parse().unwrap(), 50.0)
+        .add("GBP".parse().unwrap(), 10.0);
+
+    let valuation = portfolio.value_in(&client, "GBP").await.unwrap();
+    assert_eq!(valuation.target, "GBP");
+    assert_eq!(valuation.total, 100.0);
+    assert_eq!(valuation.positions.len(), 3);
+    assert_eq!(valuation.positions[0].rate, 0.5);
+    assert_eq!(valuation.positions[0].value, 50.0);
+    assert_eq!(valuation.positions[1].rate, 0.8);
+    assert_eq!(valuation.positions[1].value, 40.0);
+    assert_eq!(valuation.positions[2].rate, 1.0);
+    assert!(
+        valuation
+            .positions
+            .iter()
+            .all(|position| position.time_last_update_unix == 1747180802)
+    );
+
+    // A currency missing from the response can't be valued
+    portfolio.add("JPY".parse().unwrap(), 1000.0);
+    assert!(matches!(
+        portfolio.value_in(&client, "GBP").await,
+        Err(ExchangeRateError::UnsupportedCode)
+    ));
+}
+
+#[tokio::test]
+async fn test_historical_rates_served_from_cache() {
+    use crate::cache::create_cache_key;
+    use chrono::{NaiveDate, Utc};
+
+    let cache = Arc::new(InMemoryCache::new());
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .with_cache(cache.clone())
+        .build()
+        .unwrap();
+
+    let json = r#"{
+        "result":"success",
+        "documentation":"https://www.exchangerate-api.com/docs",
+        "terms_of_use":"https://www.exchangerate-api.com/terms",
+        "year":2015,
+        "month":2,
+        "day":22,
+        "base_code":"USD",
+        "conversion_rates":{"EUR":0.8812,"GBP":0.6485}
+    }"#;
+
+    // Historical entries are keyed by base and date
+    let cache_key = create_cache_key("history", &["USD", "2015", "2", "22"]);
+    cache
+        .set_raw(
+            &cache_key,
+            json.to_string(),
+            Utc::now(),
+            crate::cache::never_expires(),
+        )
+        .await
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2015, 2, 22).unwrap();
+    let historical = client.get_historical_rates("USD", date).await.unwrap();
+
+    assert_eq!(historical.base_code, "USD");
+    assert_eq!(historical.date(), Some(date));
+    assert_eq!(historical.get_rate("EUR"), Some(0.8812));
+}
+
+#[tokio::test]
+#[ignore] // Requires API key with historical data access, so ignore by default
+async fn test_get_historical_rates() {
+    use chrono::NaiveDate;
+
+    // This test requires an actual API key on a plan that includes historical data
+    let api_key = env::var("EXCHANGE_RATE_API_KEY").expect("EXCHANGE_RATE_API_KEY not set");
+
+    let client = ExchangeRateClient::builder()
+        .api_key(api_key)
+        .build()
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2020, 1, 15).unwrap();
+    let historical = client.get_historical_rates("USD", date).await.unwrap();
+
+    assert_eq!(historical.date(), Some(date));
+    assert!(historical.get_rate("EUR").is_some());
+}
+
+#[tokio::test]
+async fn test_pair_conversion_with_amount_served_from_cache() {
+    use crate::cache::create_cache_key;
+    use chrono::Utc;
+
+    let cache = Arc::new(InMemoryCache::new());
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .with_cache(cache.clone())
+        .build()
+        .unwrap();
+
+    let json = r#"{
+        "result":"success",
+        "documentation":"https://www.exchangerate-api.com/docs",
+        "terms_of_use":"https://www.exchangerate-api.com/terms",
+        "time_last_update_unix":1585267200,
+        "time_last_update_utc":"Fri, 27 Mar 2020 00:00:00 +0000",
+        "time_next_update_unix":1585353700,
+        "time_next_update_utc":"Sat, 28 Mar 2020 00:00:00 +0000",
+        "base_code":"EUR",
+        "target_code":"GBP",
+        "conversion_rate":0.8412,
+        "conversion_result":8.412
+    }"#;
+
+    // Pair entries with an amount are keyed separately from plain pair entries
+    let cache_key = create_cache_key("pair", &["EUR", "GBP", "10"]);
+    cache
+        .set_raw(
+            &cache_key,
+            json.to_string(),
+            Utc::now(),
+            Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap();
+
+    let pair = client
+        .get_pair_conversion_with_amount("EUR", "GBP", 10.0)
+        .await
+        .unwrap();
+
+    assert_eq!(pair.conversion_rate, 0.8412);
+    assert_eq!(pair.conversion_result, Some(8.412));
+    assert_eq!(pair.time_next_update_unix, 1585353700);
+}
+
+#[tokio::test]
+async fn test_retries_transient_errors() {
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond(
+            "/quota",
+            HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""),
+        )
+        .respond(
+            "/quota",
+            HttpResponse::new(StatusCode::TOO_MANY_REQUESTS, "").with_header("retry-after", "0"),
+        )
+        .respond(
+            "/quota",
+            HttpResponse::new(
+                StatusCode::OK,
+                r#"{"result":"success","documentation":"https://www.exchangerate-api.com/docs",
+                "terms_of_use":"https://www.exchangerate-api.com/terms",
+                "plan_quota":1500,"requests_remaining":1499,"refresh_day_of_month":17}"#,
+            ),
+        );
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::new(3).base_delay(Duration::from_millis(10)))
+        .build()
+        .unwrap();
+
+    let quota = client.get_quota().await.unwrap();
+    assert_eq!(quota.requests_remaining, 1499);
+    assert_eq!(transport.request_count(), 3);
+}
+
+#[tokio::test]
+async fn test_auth_errors_are_not_retried() {
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond(
+        "/quota",
+        HttpResponse::new(
+            StatusCode::FORBIDDEN,
+            r#"{"result":"error","error-type":"invalid-key"}"#,
+        ),
+    );
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::new(3).retry_if(|_| true))
+        .build()
+        .unwrap();
+
+    let result = client.get_quota().await;
+    assert!(matches!(result, Err(ExchangeRateError::InvalidKey)));
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn test_exhausted_budget_serves_stale_entries() {
+    use crate::RequestBudget;
+
+    let cache = Arc::new(InMemoryCache::new());
+    cache_latest(&cache, "USD", &[("USD", 1.0), ("EUR", 0.8)], an_hour_ago()).await;
+
+    // A spent budget without stale serving fails without calling the API
+    let transport = Arc::new(FakeTransport::new());
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache.clone())
+        .request_budget(RequestBudget::monthly(0))
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        client.get_latest_rates("USD").await,
+        Err(ExchangeRateError::BudgetExhausted)
+    ));
+    assert_eq!(client.remaining_budget().await, Some(0));
+
+    // With stale serving the expired entry is returned instead
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache.clone())
+        .request_budget(RequestBudget::monthly(0).serve_stale(true))
+        .build()
+        .unwrap();
+
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+
+    // Unless there's nothing cached
+    assert!(matches!(
+        client.get_latest_rates("EUR").await,
+        Err(ExchangeRateError::BudgetExhausted)
+    ));
+    assert_eq!(transport.request_count(), 0);
+}
+
+#[tokio::test]
+async fn test_concurrent_requests_are_coalesced() {
+    use crate::{HttpRequest, HttpTransport};
+
+    /// Answers after a delay, so that the requests overlap
+    struct SlowTransport(Arc<FakeTransport>);
+
+    #[async_trait::async_trait]
+    impl HttpTransport for SlowTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ExchangeRateError> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.0.send(request).await
+        }
+    }
+
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    let client = Arc::new(
+        ExchangeRateClient::builder()
+            .api_key("test_key")
+            .transport(Arc::new(SlowTransport(transport.clone())))
+            .disable_cache()
+            .build()
+            .unwrap(),
+    );
+
+    let tasks: Vec<_> = (0..50)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_latest_rates("USD").await })
+        })
+        .collect();
+
+    for task in tasks {
+        let rates = task.await.unwrap().unwrap();
+        assert_eq!(rates.get_rate("EUR"), Some(0.8));
+    }
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn test_stale_entries_served_when_upstream_fails_or_refreshing() {
+    use crate::cache::CacheConfig;
+    use chrono::Duration;
+
+    // Each client gets its own cache, holding an entry that expired an hour ago
+    let expired_cache = || async {
+        let cache = Arc::new(InMemoryCache::new());
+        cache_latest(&cache, "USD", &[("USD", 1.0), ("EUR", 0.8)], an_hour_ago()).await;
+        cache
+    };
+
+    // Without a stale policy, an unreachable API is an error, and the transport
+    // has no responses
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(Arc::new(FakeTransport::new()))
+        .with_cache(expired_cache().await)
+        .build()
+        .unwrap();
+    assert!(client.get_latest_rates("USD").await.is_err());
+
+    // Within the stale-if-error window, the expired entry is served and marked stale
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(Arc::new(FakeTransport::new()))
+        .with_cache(expired_cache().await)
+        .cache_config(CacheConfig {
+            stale_if_error: Some(Duration::days(1)),
+            ..CacheConfig::default()
+        })
+        .build()
+        .unwrap();
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert!(rates.stale);
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+
+    // But not beyond it
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(Arc::new(FakeTransport::new()))
+        .with_cache(expired_cache().await)
+        .cache_config(CacheConfig {
+            stale_if_error: Some(Duration::minutes(30)),
+            ..CacheConfig::default()
+        })
+        .build()
+        .unwrap();
+    assert!(client.get_latest_rates("USD").await.is_err());
+
+    // Within the stale-while-revalidate window, the expired entry is served at
+    // once while a refresh runs in the background
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(expired_cache().await)
+        .cache_config(CacheConfig {
+            stale_while_revalidate: Some(Duration::days(1)),
+            ..CacheConfig::default()
+        })
+        .build()
+        .unwrap();
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert!(rates.stale);
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+
+    // Once the refresh lands, fresh rates are served from the cache
+    for _ in 0..50 {
+        if transport.request_count() == 1 {
+            let rates = client.get_latest_rates("USD").await.unwrap();
+            if !rates.stale {
+                assert_eq!(rates.time_next_update_unix, 4102444800);
+                return;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    panic!("the background refresh didn't update the cache");
+}
+
+#[tokio::test]
+async fn test_offline_and_prefer_cache_policies() {
+    let cache = Arc::new(InMemoryCache::new());
+    cache_latest(&cache, "USD", &[("USD", 1.0), ("EUR", 0.8)], an_hour_ago()).await;
+
+    // Offline clients serve expired entries without calling the API
+    let transport = Arc::new(FakeTransport::new());
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache.clone())
+        .network_policy(NetworkPolicy::Offline)
+        .build()
+        .unwrap();
+
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert!(rates.stale);
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+
+    // And name the cache key when there's nothing cached
+    match client.get_latest_rates("EUR").await {
+        Err(ExchangeRateError::NotCached(key)) => assert_eq!(key, "latest:EUR"),
+        other => panic!("expected NotCached, got {:?}", other.map(|_| ())),
+    }
+    assert!(matches!(
+        client.get_quota().await,
+        Err(ExchangeRateError::NotCached(_))
+    ));
+    assert_eq!(transport.request_count(), 0);
+
+    // Cache-first clients serve the expired entry and only call the API on a miss
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .with_cache(cache)
+        .network_policy(NetworkPolicy::PreferCache)
+        .build()
+        .unwrap();
+
+    assert!(client.get_latest_rates("USD").await.unwrap().stale);
+    assert!(matches!(
+        client.get_latest_rates("EUR").await,
+        Err(ExchangeRateError::TransportError(_))
+    ));
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn test_refresh_scheduler() {
+    use crate::RefreshScheduler;
+    use std::sync::Mutex;
+
+    // The scheduler fetches each base on startup, warming the cache
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    let client = fake_client(&transport, AuthMethod::BearerToken);
+    let refresh = RefreshScheduler::new(client.clone(), ["USD".parse().unwrap()]).spawn();
+
+    for _ in 0..50 {
+        if transport.request_count() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    // Requests are then served from the cache
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+    assert_eq!(transport.request_count(), 1);
+
+    refresh.shutdown().await;
+
+    // In single-base mode only the single base is refreshed, as the others are
+    // derived from it
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .single_base("USD".parse().unwrap())
+        .build()
+        .unwrap();
+    let refresh = RefreshScheduler::new(
+        client.clone(),
+        ["USD".parse().unwrap(), "EUR".parse().unwrap()],
+    )
+    .spawn();
+
+    for _ in 0..50 {
+        if transport.request_count() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let rates = client.get_latest_rates("EUR").await.unwrap();
+    assert_eq!(rates.get_rate("USD"), Some(1.25));
+    assert_eq!(transport.request_count(), 1);
+    assert!(
+        transport
+            .requests()
+            .iter()
+            .all(|request| request.url.ends_with("/latest/USD"))
+    );
+
+    refresh.shutdown().await;
+
+    // Failures are reported and retried
+    let client = fake_client(&Arc::new(FakeTransport::new()), AuthMethod::BearerToken);
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let reported = failures.clone();
+    let refresh = RefreshScheduler::new(client, ["GBP".parse().unwrap()])
+        .retry_interval(Duration::from_millis(20))
+        .jitter(Duration::ZERO)
+        .on_error(move |base, _| reported.lock().unwrap().push(base.to_string()))
+        .spawn();
+
+    for _ in 0..50 {
+        if failures.lock().unwrap().len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    {
+        let failures = failures.lock().unwrap();
+        assert!(failures.len() >= 2);
+        assert!(failures.iter().all(|base| base == "GBP"));
+    }
+
+    refresh.shutdown().await;
+}
+
+/// An open access latest rates response for USD, with its own `provider` key
+#[cfg(test)]
+const OPEN_ACCESS_USD: &str = r#"{
+    "result":"success",
+    "provider":"https://www.exchangerate-api.com",
+    "documentation":"https://www.exchangerate-api.com/docs/free",
+    "terms_of_use":"https://www.exchangerate-api.com/terms",
+    "time_last_update_unix":1747180951,
+    "time_last_update_utc":"Wed, 14 May 2025 00:02:31 +0000",
+    "time_next_update_unix":4102444800,
+    "time_next_update_utc":"Fri, 01 Jan 2100 00:00:00 +0000",
+    "time_eol_unix":0,
+    "base_code":"USD",
+    "rates":{"USD":1,"EUR":0.8}
+}"#;
+
+#[tokio::test]
+async fn test_transport_auth() {
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    // Bearer tokens go in the Authorization header
+    let client = fake_client(&transport, AuthMethod::BearerToken);
+    client.get_latest_rates("USD").await.unwrap();
+
+    // In-URL keys go in the path
+    let client = fake_client(&transport, AuthMethod::InUrl);
+    client.get_latest_rates("USD").await.unwrap();
+
+    // Open access sends no key at all
+    let client = fake_client(&transport, AuthMethod::None);
+    client.get_latest_rates("USD").await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].url,
+        "https://v6.exchangerate-api.com/v6/latest/USD"
+    );
+    assert_eq!(requests[0].header("Authorization"), Some("Bearer test_key"));
+    assert_eq!(
+        requests[1].url,
+        "https://v6.exchangerate-api.com/v6/test_key/latest/USD"
+    );
+    assert_eq!(requests[1].header("Authorization"), None);
+    assert_eq!(requests[2].url, "https://open.er-api.com/v6/latest/USD");
+    assert_eq!(requests[2].header("Authorization"), None);
+}
+
+#[tokio::test]
+async fn test_amounts_applied_to_api_rates() {
+    use chrono::NaiveDate;
+
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond(
+            "/pair/EUR/GBP",
+            HttpResponse::new(
+                StatusCode::OK,
+                r#"{
+                    "result":"success",
+                    "documentation":"https://www.exchangerate-api.com/docs",
+                    "terms_of_use":"https://www.exchangerate-api.com/terms",
+                    "time_last_update_unix":1585267200,
+                    "time_last_update_utc":"Fri, 27 Mar 2020 00:00:00 +0000",
+                    "time_next_update_unix":4102444800,
+                    "time_next_update_utc":"Fri, 01 Jan 2100 00:00:00 +0000",
+                    "base_code":"EUR",
+                    "target_code":"GBP",
+                    "conversion_rate":0.5
+                }"#,
+            ),
+        )
+        .respond(
+            "/history/USD/2015/2/22",
+            HttpResponse::new(
+                StatusCode::OK,
+                r#"{
+                    "result":"success",
+                    "documentation":"https://www.exchangerate-api.com/docs",
+                    "terms_of_use":"https://www.exchangerate-api.com/terms",
+                    "year":2015,
+                    "month":2,
+                    "day":22,
+                    "base_code":"USD",
+                    "conversion_rates":{"EUR":0.5,"GBP":0.25}
+                }"#,
+            ),
+        );
+    let client = fake_client(&transport, AuthMethod::BearerToken);
+
+    let pair = client
+        .get_pair_conversion_with_amount("EUR", "GBP", 10.0)
+        .await
+        .unwrap();
+    assert_eq!(pair.conversion_result, Some(5.0));
+
+    let date = NaiveDate::from_ymd_opt(2015, 2, 22).unwrap();
+    let historical = client
+        .get_historical_rates_with_amount("USD", date, 10.0)
+        .await
+        .unwrap();
+    assert_eq!(historical.requested_amount, Some(10.0));
+    assert_eq!(historical.get_amount("GBP"), Some(2.5));
+
+    // The amounts are applied to the rates rather than sent to the API
+    let urls: Vec<_> = transport.requests().into_iter().map(|r| r.url).collect();
+    assert_eq!(
+        urls,
+        [
+            "https://v6.exchangerate-api.com/v6/pair/EUR/GBP",
+            "https://v6.exchangerate-api.com/v6/history/USD/2015/2/22",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_transport_error_decoding() {
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond(
+            "/latest/USD",
+            HttpResponse::new(
+                StatusCode::FORBIDDEN,
+                r#"{"result":"error","error-type":"invalid-key"}"#,
+            ),
+        )
+        .respond(
+            "/pair/USD/XXX",
+            HttpResponse::new(
+                StatusCode::OK,
+                r#"{"result":"error","error-type":"unsupported-code"}"#,
+            ),
+        )
+        .respond(
+            "/latest/EUR",
+            HttpResponse::new(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>"),
+        )
+        .respond(
+            "/latest/GBP",
+            HttpResponse::new(StatusCode::OK, r#"{"result":"success","base_code":"#),
+        )
+        .fail(
+            "/latest/JPY",
+            ExchangeRateError::TransportError("connection reset".to_string()),
+        );
+    let client = fake_client(&transport, AuthMethod::BearerToken);
+
+    assert!(matches!(
+        client.get_latest_rates("USD").await,
+        Err(ExchangeRateError::InvalidKey)
+    ));
+    assert!(matches!(
+        client.get_pair_conversion("USD", "XXX").await,
+        Err(ExchangeRateError::UnsupportedCode)
+    ));
+    assert!(matches!(
+        client.get_latest_rates("EUR").await,
+        Err(ExchangeRateError::HttpError(StatusCode::BAD_GATEWAY))
+    ));
+    assert!(matches!(
+        client.get_latest_rates("GBP").await,
+        Err(ExchangeRateError::JsonError(_))
+    ));
+    assert!(matches!(
+        client.get_latest_rates("JPY").await,
+        Err(ExchangeRateError::TransportError(_))
+    ));
+    // Nothing was registered for this one
+    assert!(matches!(
+        client.get_latest_rates("CHF").await,
+        Err(ExchangeRateError::TransportError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_transport_caching_and_retries() {
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond(
+            "/latest/USD",
+            HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""),
+        )
+        .respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD));
+
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::new(2).base_delay(Duration::from_millis(1)))
+        .build()
+        .unwrap();
+
+    // The 503 is retried, then the response is cached
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+    assert_eq!(transport.request_count(), 2);
+
+    // Conversions and cross rates are served from the cached rates
+    assert_eq!(client.convert(10.0, "USD", "EUR").await.unwrap(), 8.0);
+    client.get_rate_table("USD").await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+
+    // Without a cache every call goes to the API
+    let client = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .disable_cache()
+        .build()
+        .unwrap();
+    client.get_latest_rates("USD").await.unwrap();
+    client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(transport.request_count(), 4);
+}
+
+#[tokio::test]
+async fn test_client_serves_and_caches_another_provider() {
+    use crate::{FallbackProvider, RateProvider};
+
+    // The API is down, so the open access backup answers
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond(
+            "/v6/latest/USD",
+            HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""),
+        )
+        .respond(
+            "open.er-api.com/v6/latest/USD",
+            HttpResponse::new(StatusCode::OK, OPEN_ACCESS_USD),
+        );
+
+    let primary = ExchangeRateClient::builder()
+        .api_key("test_key")
+        .transport(transport.clone())
+        .disable_cache()
+        .build()
+        .unwrap();
+    let backup = ExchangeRateClient::builder()
+        .open_access()
+        .transport(transport.clone())
+        .disable_cache()
+        .build()
+        .unwrap();
+
+    // No API key is needed to serve another provider
+    let cache = Arc::new(InMemoryCache::new());
+    let client = ExchangeRateClient::builder()
+        .provider(Arc::new(FallbackProvider::new(vec![
+            Arc::new(primary),
+            Arc::new(backup),
+        ])))
+        .with_cache(cache.clone())
+        .build()
+        .unwrap();
+    assert_eq!(client.name(), "fallback");
+
+    let rates = client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(rates.get_rate("EUR"), Some(0.8));
+    assert_eq!(rates.provider.as_deref(), Some("open.er-api.com"));
+    assert_eq!(transport.request_count(), 2);
+
+    // Answers are cached under the provider's name, still naming the one that answered
+    let cached = client.get_latest_rates("USD").await.unwrap();
+    assert_eq!(cached.provider.as_deref(), Some("open.er-api.com"));
+    assert_eq!(transport.request_count(), 2);
+    assert!(cache.get_raw("fallback:latest:USD").await.is_ok());
+
+    let pair = client
+        .get_pair_conversion_with_amount("USD", "EUR", 10.0)
+        .await
+        .unwrap();
+    assert_eq!(pair.conversion_result, Some(8.0));
+    assert_eq!(pair.provider.as_deref(), Some("open.er-api.com"));
+    let requests = transport.request_count();
+    client
+        .get_pair_conversion_with_amount("USD", "EUR", 10.0)
+        .await
+        .unwrap();
+    assert_eq!(transport.request_count(), requests);
+
+    // Only exchangerate-api.com has enriched pairs and quotas
+    assert!(matches!(
+        client.get_enriched_pair("USD", "EUR").await,
+        Err(ExchangeRateError::NotSupported)
+    ));
+    assert!(matches!(
+        client.get_quota().await,
+        Err(ExchangeRateError::NotSupported)
+    ));
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn test_request_spans_redact_api_key() {
+    use std::io;
+    use std::sync::Mutex;
+
+    /// Collects everything the subscriber writes
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let output = Output::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let transport = Arc::new(FakeTransport::new());
+    transport
+        .respond("/latest/USD", HttpResponse::new(StatusCode::OK, LATEST_USD))
+        .fail(
+            "/latest/JPY",
+            ExchangeRateError::TransportError(
+                "connection to /v6/test_key/latest/JPY reset".to_string(),
+            ),
+        );
+    let client = fake_client(&transport, AuthMethod::InUrl);
+
+    client.get_latest_rates("USD").await.unwrap();
+    client.get_latest_rates("USD").await.unwrap();
+    client.get_latest_rates("JPY").await.unwrap_err();
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let completed: Vec<&str> = output
+        .lines()
+        .filter(|line| line.contains("request completed"))
+        .collect();
+    assert_eq!(completed.len(), 3, "{output}");
+
+    assert!(completed[0].contains("endpoint=\"latest\""), "{output}");
+    assert!(completed[0].contains("base=\"USD\""), "{output}");
+    assert!(completed[0].contains("cache=\"miss\""), "{output}");
+    assert!(completed[0].contains("status=\"ok\""), "{output}");
+    assert!(completed[0].contains("latency_ms="), "{output}");
+    assert!(completed[1].contains("cache=\"hit\""), "{output}");
+    assert!(completed[2].contains("status=\"error\""), "{output}");
+    assert!(output.contains("[REDACTED]/latest/USD"), "{output}");
+
+    // The key is in every URL and one error, but never in the output
+    assert!(!output.contains("test_key"), "{output}");
+}
+
+#[cfg(feature = "decimal")]
+#[tokio::test]
+async fn test_portfolio_valued_with_decimals() {
+    use crate::{Decimal, Portfolio};
+    use std::str::FromStr;
+
+    let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+    let transport = Arc::new(FakeTransport::new());
+    transport.respond(
+        "/latest/GBP",
+        HttpResponse::new(
+            StatusCode::OK,
+            r#"{"result":"success","documentation":"https://www.exchangerate-api.com/docs",
+            "terms_of_use":"https://www.exchangerate-api.com/terms",
+            "time_last_update_unix":1747180802,"time_last_update_utc":"Wed, 14 May 2025 00:00:02 +0000",
+            "time_next_update_unix":4102444800,"time_next_update_utc":"Fri, 01 Jan 2100 00:00:00 +0000",
+            "base_code":"GBP","conversion_rates":{"GBP":1,"USD":3,"EUR":1.25,"JPY":0}}"#,
+        ),
+    );
+    let client = fake_client(&transport, AuthMethod::BearerToken);
+
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .add("USD".parse().unwrap(), dec("100"))
+        .add("EUR".parse().unwrap(), dec("50.10"))
+        .add("GBP".parse().unwrap(), dec("10.10"));
+
+    // Values are divided by the quoted rate, rather than multiplied by its inverse
+    let valuation = portfolio.value_in(&client, "GBP").await.unwrap();
+    assert_eq!(valuation.target, "GBP");
+    assert_eq!(valuation.positions[0].rate, Decimal::ONE / dec("3"));
+    assert_eq!(valuation.positions[0].value, dec("100") / dec("3"));
+    assert_eq!(valuation.positions[1].value, dec("40.08"));
+    assert_eq!(valuation.positions[2].rate, Decimal::ONE);
+    assert_eq!(valuation.positions[2].value, dec("10.10"));
+    assert_eq!(
+        valuation.total,
+        dec("100") / dec("3") + dec("40.08") + dec("10.10")
+    );
+    assert_eq!(transport.request_count(), 1);
+
+    // A zero rate can't be valued
+    let jpy: Portfolio<Decimal> = [("JPY".parse().unwrap(), dec("1000"))]
+        .into_iter()
+        .collect();
+    assert!(matches!(
+        jpy.value_in(&client, "GBP").await,
+        Err(ExchangeRateError::UnsupportedCode)
+    ));
+
+    // And an overflowing total is an error rather than a wrong number
+    let huge: Portfolio<Decimal> = [
+        ("GBP".parse().unwrap(), Decimal::MAX),
+        ("GBP".parse().unwrap(), Decimal::MAX),
+    ]
+    .into_iter()
+    .collect();
+    assert!(matches!(
+        huge.value_in(&client, "GBP").await,
+        Err(ExchangeRateError::Overflow)
+    ));
+}
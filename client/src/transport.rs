@@ -0,0 +1,258 @@
+//! The HTTP transport requests are sent over
+
+use crate::ExchangeRateError;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+
+/// A GET request to the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// The full URL, including the API key with `AuthMethod::InUrl`
+    pub url: String,
+
+    /// Header names and values, e.g. `Authorization` with `AuthMethod::BearerToken`
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Look up a header value, ignoring the case of the name
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A response from the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The HTTP status
+    pub status: StatusCode,
+
+    /// Header names and values
+    pub headers: Vec<(String, String)>,
+
+    /// The response body
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Create a response with a status and body and no headers
+    #[must_use]
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a header
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Look up a header value, ignoring the case of the name
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Sends requests to the API
+///
+/// The client uses [`ReqwestTransport`] by default. Implement this trait to send
+/// requests through another HTTP stack, or use [`FakeTransport`] in tests.
+///
+/// Transports only move bytes: API error envelopes, HTTP error statuses, retries
+/// and caching are handled by the client.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// Send a GET request, returning the response whatever its status
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no response was received, e.g. because the connection
+    /// failed or timed out
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ExchangeRateError>;
+}
+
+/// The default transport, using `reqwest`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Send requests with a configured `reqwest` client, e.g. one using a proxy
+    #[must_use]
+    pub const fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ExchangeRateError> {
+        let mut request_builder = self.client.get(&request.url);
+        for (name, value) in &request.headers {
+            request_builder = request_builder.header(name, value);
+        }
+
+        let response = request_builder.send().await?;
+
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().await?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// An in-memory transport serving canned responses, for tests
+///
+/// Responses are registered for a URL path, and served to requests whose URL
+/// ends with it. Several responses for the same path are served in order, the
+/// last one repeating. Requests with no matching response fail with
+/// `ExchangeRateError::TransportError`. Every request is recorded.
+///
+/// # Examples
+///
+/// ```
+/// use client::{ExchangeRateClient, FakeTransport, HttpResponse};
+/// use reqwest::StatusCode;
+/// use std::sync::Arc;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = Arc::new(FakeTransport::new());
+/// transport.respond(
+///     "/pair/USD/EUR",
+///     HttpResponse::new(StatusCode::OK, r#"{"result":"success",
+///         "documentation":"https://www.exchangerate-api.com/docs",
+///         "terms_of_use":"https://www.exchangerate-api.com/terms",
+///         "time_last_update_unix":1747180802,
+///         "time_last_update_utc":"Wed, 14 May 2025 00:00:02 +0000",
+///         "time_next_update_unix":1747267202,
+///         "time_next_update_utc":"Thu, 15 May 2025 00:00:02 +0000",
+///         "base_code":"USD","target_code":"EUR","conversion_rate":0.8}"#),
+/// );
+///
+/// let client = ExchangeRateClient::builder()
+///     .api_key("test-key")
+///     .transport(transport.clone())
+///     .build()
+///     .unwrap();
+///
+/// let pair = client.get_pair_conversion("USD", "EUR").await.unwrap();
+/// assert_eq!(pair.conversion_rate, 0.8);
+/// assert_eq!(
+///     transport.requests()[0].header("authorization"),
+///     Some("Bearer test-key")
+/// );
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+    responses: Mutex<HashMap<String, VecDeque<Result<HttpResponse, ExchangeRateError>>>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl FakeTransport {
+    /// Create a transport with no responses
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve a response to requests for `path`, e.g. `/latest/USD`
+    pub fn respond(&self, path: impl Into<String>, response: HttpResponse) -> &Self {
+        self.push(path.into(), Ok(response))
+    }
+
+    /// Fail requests for `path` with an error, e.g. to simulate a network failure
+    pub fn fail(&self, path: impl Into<String>, error: ExchangeRateError) -> &Self {
+        self.push(path.into(), Err(error))
+    }
+
+    /// The requests sent so far, oldest first
+    #[must_use]
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The number of requests sent so far
+    #[must_use]
+    pub fn request_count(&self) -> usize {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    fn push(&self, path: String, response: Result<HttpResponse, ExchangeRateError>) -> &Self {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(path)
+            .or_default()
+            .push_back(response);
+        self
+    }
+}
+
+#[async_trait]
+impl HttpTransport for FakeTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ExchangeRateError> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request.clone());
+
+        let mut responses = self
+            .responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // The longest matching path wins, so "/latest/USD" beats "/USD"
+        let queue = responses
+            .iter_mut()
+            .filter(|(path, _)| request.url.ends_with(path.as_str()))
+            .max_by_key(|(path, _)| path.len())
+            .map(|(_, queue)| queue);
+
+        let response = match queue {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+
+        response.unwrap_or_else(|| {
+            Err(ExchangeRateError::TransportError(format!(
+                "no response for {}",
+                request.url
+            )))
+        })
+    }
+}